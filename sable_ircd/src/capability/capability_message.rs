@@ -1,21 +1,26 @@
-use crate::messages::MessageTypeFormat;
+use crate::messages::{
+    MessageSink,
+    MessageTypeFormat,
+};
 use crate::capability::{
     ClientCapability,
     ClientCapabilitySet,
 };
+use sable_network::prelude::*;
 
 #[derive(Debug,Clone)]
 pub struct CapabilityMessage<T>
 {
     message: T,
     required_caps: ClientCapabilitySet,
+    excluded_caps: ClientCapabilitySet,
 }
 
 impl<T: MessageTypeFormat> MessageTypeFormat for CapabilityMessage<T>
 {
     fn format_for_client_caps(&self, caps: &ClientCapabilitySet) -> Option<String>
     {
-        if caps.has_all(self.required_caps)
+        if caps.has_all(self.required_caps) && !caps.has_any(self.excluded_caps)
         {
             self.message.format_for_client_caps(caps)
         }
@@ -26,6 +31,14 @@ impl<T: MessageTypeFormat> MessageTypeFormat for CapabilityMessage<T>
     }
 }
 
+impl MessageTypeFormat for &dyn MessageTypeFormat
+{
+    fn format_for_client_caps(&self, caps: &ClientCapabilitySet) -> Option<String>
+    {
+        (*self).format_for_client_caps(caps)
+    }
+}
+
 pub trait CapableMessage : MessageTypeFormat + Sized
 {
     fn with_required_capabilities(self, caps: ClientCapabilitySet) -> CapabilityMessage<Self>;
@@ -34,12 +47,50 @@ pub trait CapableMessage : MessageTypeFormat + Sized
     {
         self.with_required_capabilities(cap.into())
     }
+
+    /// Send this message only to clients which have not negotiated the given capability,
+    /// typically as a fallback for a message which requires it
+    fn without_capability(self, cap: ClientCapability) -> CapabilityMessage<Self>;
 }
 
 impl<T: MessageTypeFormat + Sized> CapableMessage for T
 {
     fn with_required_capabilities(self, caps: ClientCapabilitySet) -> CapabilityMessage<Self>
     {
-        CapabilityMessage { message: self, required_caps: caps }
+        CapabilityMessage { message: self, required_caps: caps, excluded_caps: ClientCapabilitySet::new() }
+    }
+
+    fn without_capability(self, cap: ClientCapability) -> CapabilityMessage<Self>
+    {
+        CapabilityMessage { message: self, required_caps: ClientCapabilitySet::new(), excluded_caps: cap.into() }
+    }
+}
+
+/// A [`MessageSink`] which only passes on messages to clients that have not negotiated
+/// a given capability
+pub struct WithoutCapabilitySink<'a, S: MessageSink + ?Sized>
+{
+    inner: &'a S,
+    cap: ClientCapability,
+}
+
+impl<'a, S: MessageSink + ?Sized> WithoutCapabilitySink<'a, S>
+{
+    pub fn new(inner: &'a S, cap: ClientCapability) -> Self
+    {
+        Self { inner, cap }
+    }
+}
+
+impl<S: MessageSink + ?Sized> MessageSink for WithoutCapabilitySink<'_, S>
+{
+    fn send(&self, msg: &dyn MessageTypeFormat)
+    {
+        self.inner.send(&msg.without_capability(self.cap));
     }
-}
\ No newline at end of file
+
+    fn user_id(&self) -> Option<UserId>
+    {
+        self.inner.user_id()
+    }
+}
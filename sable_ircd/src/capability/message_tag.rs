@@ -1,5 +1,7 @@
 use super::*;
 use crate::messages::*;
+use sable_network::network::state::ClientTag;
+use std::fmt::Write;
 
 /// A message tag
 #[derive(Debug,Clone)]
@@ -18,13 +20,39 @@ impl MessageTag
     }
 }
 
+impl From<&ClientTag> for MessageTag
+{
+    /// Client-only tags are relayed to anyone who has negotiated `message-tags`
+    fn from(tag: &ClientTag) -> Self
+    {
+        Self::new(&tag.name, tag.value.clone().unwrap_or_default(), ClientCapability::MessageTags)
+    }
+}
+
 impl std::fmt::Display for MessageTag
 {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result
     {
         f.write_str(&self.name)?;
+
+        if self.value.is_empty()
+        {
+            return Ok(());
+        }
+
         f.write_str("=")?;
-        f.write_str(&self.value)?;
+        for c in self.value.chars()
+        {
+            match c
+            {
+                ';' => f.write_str("\\:")?,
+                ' ' => f.write_str("\\s")?,
+                '\\' => f.write_str("\\\\")?,
+                '\r' => f.write_str("\\r")?,
+                '\n' => f.write_str("\\n")?,
+                c => f.write_char(c)?,
+            }
+        }
         Ok(())
     }
 }
@@ -40,31 +68,32 @@ impl <T: MessageType> MessageTypeFormat for TaggedMessage<T>
 {
     fn format_for_client_caps(&self, caps: &ClientCapabilitySet) -> Option<String>
     {
-        let mut result = String::new();
+        let message = self.message.format_for_client_caps(caps)?;
 
-        let mut supported_tags = self.tags.iter().filter(|t| caps.has(t.required_cap));
+        let supported_tags: Vec<_> = self.tags.iter()
+                                              .filter(|t| caps.has(t.required_cap))
+                                              .map(ToString::to_string)
+                                              .collect();
 
-        if let Some(first_tag) = supported_tags.next()
+        if supported_tags.is_empty()
         {
-            result.push('@');
-            result.push_str(&first_tag.name);
-            result.push('=');
-            result.push_str(&first_tag.value);
-
-            for tag in supported_tags
-            {
-                result.push(';');
-                result.push_str(&tag.name);
-                result.push('=');
-                result.push_str(&tag.value);
-            }
-
-            result.push(' ');
+            Some(message)
         }
+        else
+        {
+            Some(prepend_tags(&supported_tags.join(";"), &message))
+        }
+    }
+}
 
-        result.push_str(&self.message.format_for_client_caps(caps)?);
-
-        Some(result)
+/// Add pre-formatted tags to a formatted message line, merging them with any tags
+/// the line already carries
+pub(crate) fn prepend_tags(tags: &str, line: &str) -> String
+{
+    match line.strip_prefix('@')
+    {
+        Some(tags_and_message) => format!("@{};{}", tags, tags_and_message),
+        None => format!("@{} {}", tags, line),
     }
 }
 
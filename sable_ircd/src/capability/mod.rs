@@ -18,6 +18,7 @@ mod with_tags;
 pub(crate) use with_tags::WithSupportedTags;
 
 pub mod server_time;
+pub mod msgid;
 
 macro_rules! define_capabilities {
     (
@@ -67,6 +68,13 @@ define_capabilities! (
         ServerTime:             0x02 => ("server-time", true),
         EchoMessage:            0x04 => ("echo-message", true),
         Sasl:                   0x08 => ("sasl", false),
+        AwayNotify:             0x10 => ("away-notify", true),
+        Batch:                  0x20 => ("batch", true),
+        EventPlayback:          0x40 => ("draft/event-playback", true),
+        LabeledResponse:        0x80 => ("labeled-response", true),
+        Setname:                0x200 => ("setname", true),
+        Chghost:                0x400 => ("chghost", true),
+        ChannelRename:          0x800 => ("draft/channel-rename", true),
 
         ChatHistory:            0x101 => ("draft/chathistory", true),
         PersistentSession:      0x102 => ("sable/persistent-session", true),
@@ -94,6 +102,11 @@ impl ClientCapabilitySet
         (self.0 & caps.0) == caps.0
     }
 
+    pub fn has_any(&self, caps: ClientCapabilitySet) -> bool
+    {
+        (self.0 & caps.0) != 0
+    }
+
     pub fn set(&mut self, cap: ClientCapability)
     {
         self.0 |= cap as u64;
@@ -0,0 +1,8 @@
+use super::*;
+use super::message_tag::MessageTag;
+use sable_network::id::MessageId;
+
+pub fn msgid_tag(id: MessageId) -> MessageTag
+{
+    MessageTag::new("msgid", id.to_msgid(), ClientCapability::MessageTags)
+}
@@ -1,5 +1,6 @@
 use super::*;
 use sable_network::history::*;
+use sable_network::network::NetworkStateChange;
 
 pub(crate) trait WithSupportedTags
 {
@@ -15,6 +16,10 @@ impl<T: TaggableMessage> WithSupportedTags for T
     {
         let server_time_tag = server_time::server_time_tag(history_entry.timestamp);
 
-        self.with_tag(server_time_tag)
+        match &history_entry.details
+        {
+            NetworkStateChange::NewMessage(detail) => self.with_tags(vec![server_time_tag, msgid::msgid_tag(detail.message.id)]),
+            _ => self.with_tag(server_time_tag)
+        }
     }
 }
\ No newline at end of file
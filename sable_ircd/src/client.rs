@@ -8,6 +8,7 @@ use crate::capability::*;
 use crate::utils::WrapOption;
 
 use std::{
+    collections::HashSet,
     net::IpAddr,
     sync::{
         atomic::{AtomicU32, Ordering},
@@ -19,6 +20,10 @@ use once_cell::sync::OnceCell;
 use arc_swap::ArcSwapOption;
 use serde::*;
 use serde_with::serde_as;
+use parking_lot::RwLock;
+
+/// Maximum number of nicknames a single connection may MONITOR
+pub const MONITOR_LIMIT: usize = 100;
 
 /// A client protocol connection
 pub struct ClientConnection
@@ -37,7 +42,10 @@ pub struct ClientConnection
     receive_queue: Movable<ThrottledQueue<String>>,
 
     /// Capability flags
-    pub capabilities: AtomicCapabilitySet
+    pub capabilities: AtomicCapabilitySet,
+
+    /// Nicknames for which this connection wants online/offline notifications
+    monitors: RwLock<HashSet<Nickname>>,
 }
 
 /// Serialised state of a [`ClientConnection`], for later resumption
@@ -49,6 +57,8 @@ pub(super) struct ClientConnectionState
     pre_client: Option<PreClient>,
     receive_queue: SavedThrottledQueue<String>,
     capabilities: ClientCapabilitySet,
+    #[serde(default)]
+    monitors: Vec<Nickname>,
 }
 
 /// Operations that, while ongoing, will block a client from registering
@@ -98,6 +108,7 @@ impl ClientConnection
             pre_client: ArcSwapOption::new(Some(Arc::new(PreClient::new()))),
             receive_queue: Movable::new(ThrottledQueue::new(throttle_settings, 16)),
             capabilities: AtomicCapabilitySet::new(),
+            monitors: RwLock::new(HashSet::new()),
         }
     }
 
@@ -112,6 +123,7 @@ impl ClientConnection
                                         .unwrap_or_else(|_| panic!("Outstanding reference to preclient while upgrading?"))),
             receive_queue: self.receive_queue.unwrap().save(),
             capabilities: (&self.capabilities).into(),
+            monitors: self.monitors.read().iter().copied().collect(),
         }
     }
 
@@ -125,6 +137,7 @@ impl ClientConnection
             pre_client: ArcSwapOption::new(state.pre_client.map(Arc::new)),
             receive_queue: Movable::new(ThrottledQueue::restore_from(state.receive_queue)),
             capabilities: state.capabilities.into(),
+            monitors: RwLock::new(state.monitors.into_iter().collect()),
         }
     }
 
@@ -185,6 +198,44 @@ impl ClientConnection
     {
         self.receive_queue.iter()
     }
+
+    /// Add a nickname to this connection's monitor list
+    ///
+    /// Returns `false` if the list is already at [`MONITOR_LIMIT`]
+    pub fn add_monitor(&self, nick: Nickname) -> bool
+    {
+        let mut monitors = self.monitors.write();
+        if monitors.len() >= MONITOR_LIMIT && !monitors.contains(&nick)
+        {
+            return false;
+        }
+        monitors.insert(nick);
+        true
+    }
+
+    /// Remove a nickname from this connection's monitor list
+    pub fn remove_monitor(&self, nick: &Nickname)
+    {
+        self.monitors.write().remove(nick);
+    }
+
+    /// Clear this connection's monitor list
+    pub fn clear_monitors(&self)
+    {
+        self.monitors.write().clear();
+    }
+
+    /// The nicknames currently monitored by this connection
+    pub fn monitors(&self) -> Vec<Nickname>
+    {
+        self.monitors.read().iter().copied().collect()
+    }
+
+    /// Determine whether this connection is monitoring the given nickname
+    pub fn is_monitoring(&self, nick: &Nickname) -> bool
+    {
+        self.monitors.read().contains(nick)
+    }
 }
 
 impl MessageSink for ClientConnection
@@ -1,4 +1,19 @@
 use client_listener::ConnectionId;
+use sable_network::network::state::ClientTag;
+
+/// Maximum size of the tag data a client may send with a message, including the leading `@`
+/// and trailing space
+pub const CLIENT_TAG_DATA_LIMIT: usize = 4094;
+
+/// Reasons a message from a client can't be tokenised
+#[derive(Debug,PartialEq)]
+pub enum ClientMessageError
+{
+    /// The message contained no command
+    Empty,
+    /// The message's tag data exceeded [`CLIENT_TAG_DATA_LIMIT`]
+    TagsTooLong,
+}
 
 /// A tokenised, but not yet processed, message from a client connection
 #[derive(Debug)]
@@ -6,6 +21,8 @@ pub struct ClientMessage
 {
     /// The connection from which the message was received
     pub source: ConnectionId,
+    /// Message tags supplied with the message
+    pub tags: Vec<ClientTag>,
     /// The command
     pub command: String,
     /// The list of arguments
@@ -15,21 +32,35 @@ pub struct ClientMessage
 impl ClientMessage
 {
     /// Create a `ClientMessage` from a received message
-    pub fn parse(source: ConnectionId, raw: &str) -> Option<Self>
+    pub fn parse(source: ConnectionId, raw: &str) -> Result<Self, ClientMessageError>
     {
         let mut args = Vec::new();
-        let raw = raw.trim_start();
+        let mut raw = raw.trim_start();
+        let mut tags = Vec::new();
+
+        if let Some(tag_str) = raw.strip_prefix('@')
+        {
+            let (tag_str, rest) = tag_str.split_once(' ').unwrap_or((tag_str, ""));
+            if tag_str.len() + 2 > CLIENT_TAG_DATA_LIMIT
+            {
+                return Err(ClientMessageError::TagsTooLong);
+            }
+            tags = Self::parse_tags(tag_str);
+            raw = rest.trim_start();
+        }
+
         if raw.is_empty()
         {
-            return None;
+            return Err(ClientMessageError::Empty);
         }
 
         let offset = match raw.find(' ')
         {
             Some(offset) => offset,
             None => {
-                return Some(Self {
+                return Ok(Self {
                     source,
+                    tags,
                     command: raw.to_string(),
                     args: Vec::new()
                 });
@@ -74,12 +105,76 @@ impl ClientMessage
             }
         }
 
-        Some(Self {
+        Ok(Self {
             source,
+            tags,
             command: command.to_string(),
             args
         })
     }
+
+    fn parse_tags(tag_str: &str) -> Vec<ClientTag>
+    {
+        tag_str.split(';')
+               .filter(|t| !t.is_empty())
+               .map(|tag| match tag.split_once('=')
+               {
+                   Some((name, value)) => ClientTag {
+                       name: name.to_string(),
+                       value: Some(unescape_tag_value(value)).filter(|v| !v.is_empty())
+                   },
+                   None => ClientTag { name: tag.to_string(), value: None }
+               })
+               .filter(|tag| is_valid_tag_name(&tag.name))
+               .collect()
+    }
+}
+
+/// Check that a tag name has the form `[+][vendor/]key`, where the vendor is a hostname and
+/// the key consists of letters, digits and hyphens
+fn is_valid_tag_name(name: &str) -> bool
+{
+    let name = name.strip_prefix('+').unwrap_or(name);
+    let (vendor, key) = match name.rsplit_once('/')
+    {
+        Some((vendor, key)) => (Some(vendor), key),
+        None => (None, name)
+    };
+
+    let vendor_valid = vendor.map(|v| !v.is_empty() && v.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.'))
+                             .unwrap_or(true);
+    let key_valid = !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+
+    vendor_valid && key_valid
+}
+
+/// Reverse the escaping applied to message tag values
+fn unescape_tag_value(value: &str) -> String
+{
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next()
+    {
+        if c != '\\'
+        {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next()
+        {
+            Some(':') => result.push(';'),
+            Some('s') => result.push(' '),
+            Some('r') => result.push('\r'),
+            Some('n') => result.push('\n'),
+            Some(other) => result.push(other),
+            // A trailing backslash is dropped
+            None => ()
+        }
+    }
+
+    result
 }
 
 #[cfg(test)]
@@ -147,7 +242,41 @@ mod tests
     #[test]
     fn empty()
     {
-        assert!(ClientMessage::parse(get_connid(), "").is_none());
+        assert_eq!(ClientMessage::parse(get_connid(), "").unwrap_err(), ClientMessageError::Empty);
+    }
+
+    #[test]
+    fn tags()
+    {
+        let msg = ClientMessage::parse(get_connid(), "@+draft/reply=abc;+typing;label=x\\sy\\:z command arg1").unwrap();
+
+        assert_eq!(msg.command, "command");
+        assert_eq!(msg.args, &["arg1"]);
+        assert_eq!(msg.tags, &[
+            ClientTag { name: "+draft/reply".to_string(), value: Some("abc".to_string()) },
+            ClientTag { name: "+typing".to_string(), value: None },
+            ClientTag { name: "label".to_string(), value: Some("x y;z".to_string()) },
+        ]);
+    }
+
+    #[test]
+    fn malformed_tag_names()
+    {
+        let msg = ClientMessage::parse(get_connid(), "@+ok;+bad_name;+/x;+vendor.example/ok-2;=v;+a/ command").unwrap();
+
+        let names: Vec<_> = msg.tags.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, &["+ok", "+vendor.example/ok-2"]);
+    }
+
+    #[test]
+    fn tag_data_limit()
+    {
+        let value = "a".repeat(CLIENT_TAG_DATA_LIMIT - "@+t= ".len());
+        assert!(ClientMessage::parse(get_connid(), &format!("@+t={} command", value)).is_ok());
+
+        let value = "a".repeat(CLIENT_TAG_DATA_LIMIT);
+        assert_eq!(ClientMessage::parse(get_connid(), &format!("@+t={} command", value)).unwrap_err(),
+                   ClientMessageError::TagsTooLong);
     }
 
     #[test]
@@ -1,5 +1,11 @@
 use super::{*, plumbing::{Command,CommandExt}};
 use sable_network::{network::wrapper::ObjectWrapper, policy::*};
+use crate::capability::{
+    ClientCapability,
+    TaggableMessage,
+    message_tag::{MessageTag, prepend_tags},
+};
+use parking_lot::Mutex;
 
 /// Describes the possible types of connection that can invoke a command handler
 pub enum CommandSource<'a>
@@ -32,6 +38,12 @@ pub struct ClientCommand
     pub command: String,
     /// Arguments supplied
     pub args: Vec<String>,
+    /// Message tags supplied
+    pub tags: Vec<state::ClientTag>,
+    /// The `label` tag supplied, if the connection has negotiated `labeled-response`
+    label: Option<String>,
+    /// Formatted responses held back so that they can be labeled once the command completes
+    labeled_responses: Mutex<Vec<String>>,
 }
 
 // Safety: this isn't automatically Send/Sync because of the raw pointer inside `InternalCommandSource`.
@@ -50,6 +62,15 @@ impl ClientCommand
         let net = server.network();
         let source = Self::translate_message_source(&*net, &*connection)?;
 
+        let label = if connection.capabilities.has(ClientCapability::LabeledResponse)
+        {
+            message.tags.iter().find(|t| t.name == "label").and_then(|t| t.value.clone())
+        }
+        else
+        {
+            None
+        };
+
         Ok(Self {
             server,
             connection,
@@ -57,6 +78,9 @@ impl ClientCommand
             source,
             command: message.command,
             args: message.args,
+            tags: message.tags,
+            label,
+            labeled_responses: Mutex::new(Vec::new()),
         })
     }
 
@@ -107,6 +131,11 @@ impl Command for ClientCommand
         ArgListIter::new(&self.args)
     }
 
+    fn tags(&self) -> &[state::ClientTag]
+    {
+        &self.tags
+    }
+
     fn server(&self) -> &Arc<ClientServer>
     {
         &self.server
@@ -127,7 +156,17 @@ impl Command for ClientCommand
 
     fn response(&self, m: &dyn messages::MessageTypeFormat)
     {
-        self.connection.send(m);
+        if self.label.is_some()
+        {
+            if let Some(line) = m.format_for_client_caps(&(&self.connection.capabilities).into())
+            {
+                self.labeled_responses.lock().push(line);
+            }
+        }
+        else
+        {
+            self.connection.send(m);
+        }
     }
 
     fn connection_id(&self) -> client_listener::ConnectionId
@@ -146,6 +185,44 @@ impl Command for ClientCommand
     }
 }
 
+impl Drop for ClientCommand
+{
+    // Commands are dropped once their handler, sync or async, has finished; this is where
+    // any held-back responses are labeled and sent
+    fn drop(&mut self)
+    {
+        let label = match self.label.take()
+        {
+            Some(label) => label,
+            None => return
+        };
+        let label_tag = MessageTag::new("label", label, ClientCapability::LabeledResponse);
+        let mut responses = std::mem::take(self.labeled_responses.get_mut());
+
+        match responses.len()
+        {
+            0 =>
+            {
+                self.connection.send(&message::Ack::new(&*self.server).with_tag(label_tag));
+            }
+            1 =>
+            {
+                let line = responses.remove(0);
+                self.connection.send(&prepend_tags(&label_tag.to_string(), &line));
+            }
+            _ =>
+            {
+                let batch = messages::BatchSink::start_with_tags(&*self.connection, "labeled-response", &[], vec![label_tag]);
+                for line in responses
+                {
+                    batch.send(&line);
+                }
+                batch.end();
+            }
+        }
+    }
+}
+
 impl ClientCommand
 {
     fn translate_command_error(&self, err: CommandError) -> Option<Box<dyn Numeric>>
@@ -202,6 +279,9 @@ impl ClientCommand
             CommandError::Permission(pe) => {
                 match pe
                 {
+                    PermissionError::User(UserPermissionError::MissingPrivilege(privilege)) => {
+                        Some(Box::new(make_numeric!(NoPrivs, &privilege.to_string())))
+                    }
                     // These have no corresponding numerics
                     PermissionError::User(_) => None,
                     PermissionError::Registration(_) => None,
@@ -0,0 +1,25 @@
+use super::*;
+
+#[command_handler("AWAY")]
+fn handle_away(server: &ClientServer, source: UserSource, cmd: &dyn Command,
+               reason: Option<&str>) -> CommandResult
+{
+    let reason = reason.filter(|r| !r.is_empty());
+
+    if reason.is_some()
+    {
+        cmd.numeric(make_numeric!(NowAway));
+    }
+    else
+    {
+        cmd.numeric(make_numeric!(Unaway));
+    }
+
+    let details = event::details::UserAwayChange {
+        reason: reason.map(ToOwned::to_owned),
+        automatic: false,
+    };
+    server.add_action(CommandAction::state_change(source.id(), details));
+
+    Ok(())
+}
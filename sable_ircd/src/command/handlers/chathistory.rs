@@ -1,11 +1,13 @@
 use super::*;
 use crate::utils;
+use crate::capability::ClientCapability;
 use messages::send_history::SendHistoryItem;
 use sable_network::network::update::HistoricMessageTarget;
 
 use std::cmp::{
     max,
     min,
+    Ordering,
 };
 
 #[command_handler("CHATHISTORY")]
@@ -13,13 +15,17 @@ fn handle_chathistory(source: UserSource, server: &ClientServer, cmd: &dyn Comma
                       subcommand: &str, arg_1: &str, arg_2: &str, arg_3: &str, arg_4: Option<&str>) -> CommandResult
 {
     let source = source.deref();
+    let net = cmd.network();
+
+    // Events other than messages are only played back to clients that have asked for them
+    let event_playback = cmd.connection().capabilities.has(ClientCapability::EventPlayback);
 
     match subcommand.to_ascii_uppercase().as_str()
     {
         "TARGETS" =>
         {
-            let from_ts = utils::parse_timestamp(arg_1);
-            let to_ts = utils::parse_timestamp(arg_2);
+            let from_ts = utils::parse_timestamp(arg_1.strip_prefix("timestamp=").unwrap_or(arg_1));
+            let to_ts = utils::parse_timestamp(arg_2.strip_prefix("timestamp=").unwrap_or(arg_2));
             let limit = arg_3.parse().ok();
 
             if from_ts.is_none() || to_ts.is_none()
@@ -34,19 +40,19 @@ fn handle_chathistory(source: UserSource, server: &ClientServer, cmd: &dyn Comma
             }
 
             // The spec allows the from and to timestamps in either order; list_targets requires from < to
-            list_targets(server, cmd, source, min(from_ts, to_ts), max(from_ts, to_ts), limit);
+            list_targets(server, cmd, source, min(from_ts, to_ts), max(from_ts, to_ts), limit, event_playback);
         }
         "LATEST" =>
         {
-            let target = arg_1.clone();
-            let from_ts = match arg_2
+            let target = arg_1;
+            let from = match arg_2
             {
                 "*" => None,
-                _ => match utils::parse_timestamp(arg_2)
+                _ => match parse_bound(server, source, arg_2)
                 {
-                    Some(ts) => Some(ts),
+                    Some(bound) => Some(bound),
                     None => {
-                        cmd.response(&message::Fail::new("CHATHISTORY", "INVALID_PARAMS", "", "Invalid timestamp"));
+                        cmd.response(&message::Fail::new("CHATHISTORY", "INVALID_PARAMS", "", "Invalid message reference"));
                         return Ok(());
                     }
                 }
@@ -59,16 +65,17 @@ fn handle_chathistory(source: UserSource, server: &ClientServer, cmd: &dyn Comma
                 return Ok(());
             }
 
-            send_history_for_target_reverse(server, cmd, source, &target, from_ts, None, limit)?;
+            let filter = TargetFilter { net, for_user: source.id(), target, event_playback };
+            send_batched(cmd, target, |batch| send_history_for_target_reverse(server, batch, &filter, from, None, limit))?;
         }
         "BEFORE" =>
         {
             let target = arg_1.to_string();
-            let end_ts = match utils::parse_timestamp(arg_2)
+            let end = match parse_bound(server, source, arg_2)
             {
-                Some(ts) => ts,
+                Some(bound) => bound,
                 None => {
-                    cmd.response(&message::Fail::new("CHATHISTORY", "INVALID_PARAMS", "", "Invalid timestamp"));
+                    cmd.response(&message::Fail::new("CHATHISTORY", "INVALID_PARAMS", "", "Invalid message reference"));
                     return Ok(());
                 }
             };
@@ -80,16 +87,17 @@ fn handle_chathistory(source: UserSource, server: &ClientServer, cmd: &dyn Comma
                 return Ok(());
             }
 
-            send_history_for_target_reverse(server, cmd, source, &target, None, Some(end_ts), limit)?;
+            let filter = TargetFilter { net, for_user: source.id(), target: &target, event_playback };
+            send_batched(cmd, &target, |batch| send_history_for_target_reverse(server, batch, &filter, None, Some(end), limit))?;
         }
         "AFTER" =>
         {
-            let target = arg_1.clone();
-            let start_ts = match utils::parse_timestamp(arg_2)
+            let target = arg_1;
+            let start = match parse_bound(server, source, arg_2)
             {
-                Some(ts) => ts,
+                Some(bound) => bound,
                 None => {
-                    cmd.response(&message::Fail::new("CHATHISTORY", "INVALID_PARAMS", "", "Invalid timestamp"));
+                    cmd.response(&message::Fail::new("CHATHISTORY", "INVALID_PARAMS", "", "Invalid message reference"));
                     return Ok(());
                 }
             };
@@ -101,16 +109,17 @@ fn handle_chathistory(source: UserSource, server: &ClientServer, cmd: &dyn Comma
                 return Ok(());
             }
 
-            send_history_for_target_forward(server, cmd, source, &target, Some(start_ts), None, limit)?;
+            let filter = TargetFilter { net, for_user: source.id(), target, event_playback };
+            send_batched(cmd, target, |batch| send_history_for_target_forward(server, batch, &filter, Some(start), None, limit))?;
         }
         "AROUND" =>
         {
-            let target = arg_1.clone();
-            let around_ts = match utils::parse_timestamp(arg_2)
+            let target = arg_1;
+            let around = match parse_bound(server, source, arg_2)
             {
-                Some(ts) => ts,
+                Some(bound) => bound,
                 None => {
-                    cmd.response(&message::Fail::new("CHATHISTORY", "INVALID_PARAMS", "", "Invalid timestamp"));
+                    cmd.response(&message::Fail::new("CHATHISTORY", "INVALID_PARAMS", "", "Invalid message reference"));
                     return Ok(());
                 }
             };
@@ -125,25 +134,28 @@ fn handle_chathistory(source: UserSource, server: &ClientServer, cmd: &dyn Comma
                 }
             };
 
-            send_history_for_target_reverse(server, cmd, source, &target, Some(around_ts), None, Some(limit/2))?;
-            send_history_for_target_forward(server, cmd, source, &target, Some(around_ts), None, Some(limit/2))?;
+            let filter = TargetFilter { net, for_user: source.id(), target, event_playback };
+            send_batched(cmd, target, |batch| {
+                send_history_for_target_reverse(server, batch, &filter, None, Some(around), Some(limit/2))?;
+                send_history_for_target_forward(server, batch, &filter, Some(around), None, Some(limit/2))
+            })?;
         }
         "BETWEEN" =>
         {
-            let target = arg_1.clone();
-            let start_ts = match utils::parse_timestamp(arg_2)
+            let target = arg_1;
+            let start = match parse_bound(server, source, arg_2)
             {
-                Some(ts) => ts,
+                Some(bound) => bound,
                 None => {
-                    cmd.response(&message::Fail::new("CHATHISTORY", "INVALID_PARAMS", "", "Invalid timestamp"));
+                    cmd.response(&message::Fail::new("CHATHISTORY", "INVALID_PARAMS", "", "Invalid message reference"));
                     return Ok(());
                 }
             };
-            let end_ts = match utils::parse_timestamp(arg_3)
+            let end = match parse_bound(server, source, arg_3)
             {
-                Some(ts) => ts,
+                Some(bound) => bound,
                 None => {
-                    cmd.response(&message::Fail::new("CHATHISTORY", "INVALID_PARAMS", "", "Invalid timestamp"));
+                    cmd.response(&message::Fail::new("CHATHISTORY", "INVALID_PARAMS", "", "Invalid message reference"));
                     return Ok(());
                 }
             };
@@ -155,7 +167,18 @@ fn handle_chathistory(source: UserSource, server: &ClientServer, cmd: &dyn Comma
                 return Ok(());
             }
 
-            send_history_for_target_forward(server, cmd, source.deref(), &target, Some(start_ts), Some(end_ts), limit)?;
+            let filter = TargetFilter { net, for_user: source.id(), target, event_playback };
+
+            // The references may be given in either order. If the start is later than the end,
+            // the limit applies counting backwards from the start.
+            if start.is_after(&end)
+            {
+                send_batched(cmd, target, |batch| send_history_for_target_reverse(server, batch, &filter, Some(end), Some(start), limit))?;
+            }
+            else
+            {
+                send_batched(cmd, target, |batch| send_history_for_target_forward(server, batch, &filter, Some(start), Some(end), limit))?;
+            }
         }
         _ =>
         {
@@ -169,10 +192,14 @@ fn handle_chathistory(source: UserSource, server: &ClientServer, cmd: &dyn Comma
 // Helper to extract the target name for chathistory purposes from a given event.
 // This might be the source or target of the actual event, or might be None if it's
 // an event type that we don't include in history playback
-fn target_name_for_entry(for_user: UserId, entry: &HistoryLogEntry) -> Option<String>
+fn target_name_for_entry(for_user: UserId, entry: &HistoryLogEntry, event_playback: bool) -> Option<String>
 {
     match &entry.details
     {
+        NetworkStateChange::NewMessage(message) if !event_playback && message.message.message_type == state::MessageType::Tagmsg =>
+        {
+            None
+        }
         NetworkStateChange::NewMessage(message) =>
         {
             if matches!(&message.target, HistoricMessageTarget::User(user) if user.user.id == for_user)
@@ -188,9 +215,118 @@ fn target_name_for_entry(for_user: UserId, entry: &HistoryLogEntry) -> Option<St
     }
 }
 
+/// Selects the history entries which belong to a single conversation
+struct TargetFilter<'a>
+{
+    net: &'a Network,
+    for_user: UserId,
+    target: &'a str,
+    event_playback: bool,
+}
+
+impl TargetFilter<'_>
+{
+    fn matches(&self, entry: &HistoryLogEntry) -> bool
+    {
+        let channel = match &entry.details
+        {
+            NetworkStateChange::NewMessage(_) =>
+            {
+                return target_name_for_entry(self.for_user, entry, self.event_playback).as_deref() == Some(self.target);
+            }
+            _ if !self.event_playback => return false,
+            NetworkStateChange::ChannelJoin(detail) => &detail.channel,
+            NetworkStateChange::ChannelPart(detail) => &detail.channel,
+            NetworkStateChange::ChannelKick(detail) => &detail.channel,
+            NetworkStateChange::ChannelTopicChange(detail) => &detail.channel,
+            NetworkStateChange::ChannelRename(detail) => &detail.channel,
+            NetworkStateChange::ChannelModeChange(detail) => &detail.channel,
+            NetworkStateChange::ListModeAdded(detail) => &detail.channel,
+            NetworkStateChange::ListModeRemoved(detail) => &detail.channel,
+            NetworkStateChange::MembershipFlagChange(detail) => &detail.channel,
+            NetworkStateChange::UserQuit(detail) =>
+            {
+                // Quits don't record channel names, so look up the channels the user was in
+                return detail.memberships.iter().any(|m| {
+                    matches!(self.net.channel(m.channel), Ok(channel) if channel.name().value().as_str() == self.target)
+                });
+            }
+            _ => return false
+        };
+
+        channel.name.value().as_str() == self.target
+    }
+}
+
+/// A position in a user's history, bounding a CHATHISTORY query
+#[derive(Clone,Copy)]
+enum HistoryBound
+{
+    Timestamp(i64),
+    Entry(LogEntryId, i64),
+}
+
+impl HistoryBound
+{
+    /// Determine where the given entry falls relative to this bound. Message ID bounds
+    /// compare by position in the log, so that messages sharing a timestamp are kept distinct.
+    fn compare(&self, entry: &HistoryLogEntry) -> Ordering
+    {
+        match self
+        {
+            Self::Timestamp(ts) => entry.timestamp.cmp(ts),
+            Self::Entry(id, _) => entry.id.cmp(id),
+        }
+    }
+
+    /// Determine whether this bound refers to a later point in history than `other`
+    fn is_after(&self, other: &HistoryBound) -> bool
+    {
+        match (self, other)
+        {
+            (Self::Entry(id, _), Self::Entry(other_id, _)) => id > other_id,
+            _ => self.timestamp() > other.timestamp(),
+        }
+    }
+
+    fn timestamp(&self) -> i64
+    {
+        match self
+        {
+            Self::Timestamp(ts) | Self::Entry(_, ts) => *ts
+        }
+    }
+}
+
+// Parse a `timestamp=` or `msgid=` message reference. A bare timestamp is also accepted.
+fn parse_bound(server: &ClientServer, source: &wrapper::User, arg: &str) -> Option<HistoryBound>
+{
+    if let Some(msgid) = arg.strip_prefix("msgid=")
+    {
+        let message_id = MessageId::from_msgid(msgid)?;
+        let log = server.node().history();
+        let entry = log.entry_for_message(source.id(), message_id)?;
+        Some(HistoryBound::Entry(entry.id, entry.timestamp))
+    }
+    else
+    {
+        let ts = arg.strip_prefix("timestamp=").unwrap_or(arg);
+        utils::parse_timestamp(ts).map(HistoryBound::Timestamp)
+    }
+}
+
+// Run `f` with a sink which wraps everything it sends in a `chathistory` batch
+fn send_batched<'a>(cmd: &'a dyn Command, target: &str, f: impl FnOnce(&BatchSink<'a, dyn Command + 'a>) -> CommandResult) -> CommandResult
+{
+    let batch = BatchSink::start(cmd, "chathistory", &[target]);
+    let result = f(&batch);
+    batch.end();
+    result
+}
+
 // For listing targets, we iterate backwards through time; this allows us to just collect the
 // first timestamp we see for each target and know that it's the most recent one
-fn list_targets(server: &ClientServer, into: &(impl MessageSink + ?Sized), source: &wrapper::User, from_ts: Option<i64>, to_ts: Option<i64>, limit: Option<usize>)
+fn list_targets(server: &ClientServer, into: &(impl MessageSink + ?Sized), source: &wrapper::User, from_ts: Option<i64>, to_ts: Option<i64>, limit: Option<usize>, event_playback: bool)
 {
     let log = server.node().history();
     let mut found_targets = HashMap::new();
@@ -210,7 +346,7 @@ fn list_targets(server: &ClientServer, into: &(impl MessageSink + ?Sized), sourc
         }
 
 
-        if let Some(target_name) = target_name_for_entry(source.id(), entry)
+        if let Some(target_name) = target_name_for_entry(source.id(), entry, event_playback)
         {
             found_targets.entry(target_name).or_insert(entry.timestamp);
         }
@@ -228,30 +364,27 @@ fn list_targets(server: &ClientServer, into: &(impl MessageSink + ?Sized), sourc
     }
 }
 
-fn send_history_for_target_forward(server: &ClientServer, into: &(impl MessageSink + ?Sized), source: &wrapper::User, target: &str, from_ts: Option<i64>, to_ts: Option<i64>, limit: Option<usize>) -> CommandResult
+fn send_history_for_target_forward(server: &ClientServer, into: &(impl MessageSink + ?Sized), filter: &TargetFilter, from: Option<HistoryBound>, to: Option<HistoryBound>, limit: Option<usize>) -> CommandResult
 {
     let log = server.node().history();
     let mut entries = Vec::new();
 
-    for entry in log.entries_for_user(source.id())
+    for entry in log.entries_for_user(filter.for_user)
     {
-        if matches!(from_ts, Some(ts) if entry.timestamp <= ts)
+        if matches!(from, Some(bound) if bound.compare(entry) != Ordering::Greater)
         {
             // Skip over until we hit the timestamp window we're interested in
             continue;
         }
-        if matches!(to_ts, Some(ts) if entry.timestamp >= ts)
+        if matches!(to, Some(bound) if bound.compare(entry) != Ordering::Less)
         {
             // If we hit this then we've passed the requested window and should stop
             break;
         }
 
-        if let Some(event_target) = target_name_for_entry(source.id(), entry)
+        if filter.matches(entry)
         {
-            if event_target == target
-            {
-                entries.push(entry);
-            }
+            entries.push(entry);
         }
 
         if matches!(limit, Some(limit) if limit <= entries.len())
@@ -268,31 +401,29 @@ fn send_history_for_target_forward(server: &ClientServer, into: &(impl MessageSi
     Ok(())
 }
 
-// As above, but work backwards
-fn send_history_for_target_reverse(server: &ClientServer, into: &(impl MessageSink + ?Sized), source: &wrapper::User, target: &str, from_ts: Option<i64>, to_ts: Option<i64>, limit: Option<usize>) -> CommandResult
+// As above, but work backwards from the end of the window
+fn send_history_for_target_reverse(server: &ClientServer, into: &(impl MessageSink + ?Sized), filter: &TargetFilter, from: Option<HistoryBound>, to: Option<HistoryBound>, limit: Option<usize>) -> CommandResult
 {
     let log = server.node().history();
     let mut entries = Vec::new();
 
-    for entry in log.entries_for_user_reverse(source.id())
+    for entry in log.entries_for_user_reverse(filter.for_user)
     {
-        if matches!(from_ts, Some(ts) if entry.timestamp <= ts)
+        if matches!(to, Some(bound) if bound.compare(entry) != Ordering::Less)
         {
-            // Skip over until we hit the timestamp window we're interested in
+            // Skip over until we hit the window we're interested in
             continue;
         }
-        if matches!(to_ts, Some(ts) if entry.timestamp >= ts)
+        if matches!(from, Some(bound) if bound.compare(entry) != Ordering::Greater)
         {
-            // If we hit this then we've passed the requested window and should stop
+            // We're iterating backwards through time; if we hit this then we've
+            // passed the requested window and should stop
             break;
         }
 
-        if let Some(event_target) = target_name_for_entry(source.id(), entry)
+        if filter.matches(entry)
         {
-            if event_target == target
-            {
-                entries.push(entry);
-            }
+            entries.push(entry);
         }
 
         if matches!(limit, Some(limit) if limit <= entries.len())
@@ -0,0 +1,40 @@
+use super::*;
+use event::*;
+use state::{
+    AuditLogCategory,
+    AuditLogField
+};
+use std::str::FromStr;
+
+#[command_handler("CHGHOST")]
+fn handle_chghost(server: &ClientServer, source: UserSource,
+                  target: wrapper::User, username: &str, hostname: &str) -> CommandResult
+{
+    server.policy().require_oper(&source)?;
+
+    server.policy().can_change_host(&source, &target)?;
+
+    let username = Username::from_str(username)
+                        .map_err(|_| CommandError::InvalidArgument(username.to_string(), "username".to_string()))?;
+    let hostname = Hostname::from_str(hostname)
+                        .map_err(|_| CommandError::InvalidArgument(hostname.to_string(), "hostname".to_string()))?;
+
+    let audit = details::NewAuditLogEntry {
+        category: AuditLogCategory::General,
+        fields: vec![
+            (AuditLogField::Source, source.nuh()),
+            (AuditLogField::ActionType, "CHGHOST".to_string()),
+            (AuditLogField::TargetUser, target.nuh()),
+            (AuditLogField::Reason, format!("{}@{}", username, hostname))
+        ]
+    };
+    server.add_action(CommandAction::state_change(server.ids().next_audit_log_entry(), audit));
+
+    server.add_action(CommandAction::state_change(target.id(), details::UserDetailsChange {
+        username: Some(username),
+        visible_host: Some(hostname),
+        realname: None,
+    }));
+
+    Ok(())
+}
@@ -19,7 +19,7 @@ fn handle_join(server: &ClientServer, net: &Network, source: UserSource,
             {
                 server.policy().can_join(source.as_ref(), &channel, key)?;
 
-                (channel.id(), MembershipFlagSet::new())
+                (channel.id(), server.policy().auto_permissions(source.as_ref(), &channel))
             },
             Err(_) =>
             {
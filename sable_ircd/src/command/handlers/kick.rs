@@ -0,0 +1,37 @@
+use super::*;
+
+#[command_handler("KICK")]
+fn handle_kick(server: &ClientServer, net: &Network, source: UserSource, cmd: &dyn Command,
+               channel: wrapper::Channel, targets: &str, msg: Option<&str>) -> CommandResult
+{
+    let default_message = source.nick().to_string();
+    let message = msg.unwrap_or(&default_message);
+
+    for target_name in targets.split(',')
+    {
+        if let Err(e) = kick_one(server, net, &source, &channel, target_name, message)
+        {
+            cmd.notify_error(e);
+        }
+    }
+    Ok(())
+}
+
+fn kick_one(server: &ClientServer, net: &Network, source: &wrapper::User, channel: &wrapper::Channel,
+            target_name: &str, message: &str) -> CommandResult
+{
+    let target = net.user_by_nick(&Nickname::from_str(target_name)?)?;
+
+    let membership = target.is_in_channel(channel.id())
+                           .ok_or_else(|| make_numeric!(UserNotOnChannel, &target, channel))?;
+
+    server.policy().can_kick(source, channel, &target)?;
+
+    let details = event::ChannelKick {
+        source: source.id().into(),
+        message: message.to_owned(),
+    };
+    server.add_action(CommandAction::state_change(membership.id(), details));
+
+    Ok(())
+}
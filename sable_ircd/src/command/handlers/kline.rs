@@ -24,6 +24,8 @@ fn handle_kline(server: &ClientServer, cmd: &dyn Command, source: UserSource,
 
     if let [user, host] = mask_parts[..]
     {
+        server.policy().can_set_kline(&source, &Pattern::new(user.to_string()), &Pattern::new(host.to_string()), duration as i64)?;
+
         let audit = details::NewAuditLogEntry {
             category: AuditLogCategory::NetworkBan,
             fields: vec![
@@ -63,3 +65,41 @@ fn handle_kline(server: &ClientServer, cmd: &dyn Command, source: UserSource,
     Ok(())
 }
 
+#[command_handler("UNKLINE")]
+fn handle_unkline(server: &ClientServer, net: &Network, cmd: &dyn Command, source: UserSource,
+                  mask: &str) -> CommandResult
+{
+    server.policy().require_oper(&source)?;
+    server.policy().can_remove_kline(&source)?;
+
+    let Some((user, host)) = mask.split_once('@') else {
+        cmd.notice("Invalid kline mask");
+        return Ok(())
+    };
+
+    let Ok(matcher) = NetworkBanMatch::from_user_host(user, host) else {
+        cmd.notice("Invalid kline mask");
+        return Ok(())
+    };
+
+    let Some(ban) = net.network_bans().find_by_matcher(&matcher) else {
+        cmd.notice(format_args!("No network ban found for {}", mask));
+        return Ok(())
+    };
+
+    let audit = details::NewAuditLogEntry {
+        category: AuditLogCategory::NetworkBan,
+        fields: vec![
+            (AuditLogField::Source, source.nuh()),
+            (AuditLogField::ActionType, "UNKLINE".to_string()),
+            (AuditLogField::NetworkBanMask, mask.to_string()),
+        ]
+    };
+    server.add_action(CommandAction::state_change(server.ids().next_audit_log_entry(), audit));
+
+    server.node().submit_event(ban.id, details::RemoveNetworkBan {
+        remover: source.id().into()
+    });
+
+    Ok(())
+}
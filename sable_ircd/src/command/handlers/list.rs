@@ -0,0 +1,165 @@
+use super::*;
+
+/// A single ELIST filter, as advertised in ISUPPORT
+#[derive(Debug,PartialEq)]
+enum ListFilter
+{
+    Mask(Pattern),
+    NotMask(Pattern),
+    MoreUsers(usize),
+    FewerUsers(usize),
+    CreatedBefore(i64),
+    CreatedAfter(i64),
+    TopicBefore(i64),
+    TopicAfter(i64),
+}
+
+impl ListFilter
+{
+    /// Parse a filter. Time-based filters are given in minutes relative to `now`. Returns
+    /// `None` if the filter is malformed.
+    fn parse(s: &str, now: i64) -> Option<Self>
+    {
+        let minutes_ago = |n: &str| n.parse::<i64>().ok().map(|n| now - n * 60);
+
+        Some(match s.as_bytes().first()?
+        {
+            // <0 is accepted, and matches nothing
+            b'<' => Self::FewerUsers(s[1..].parse::<usize>().ok()?),
+            b'>' => Self::MoreUsers(s[1..].parse::<usize>().ok()?),
+            b'!' => Self::NotMask(Pattern::new(s[1..].to_ascii_lowercase())),
+            b'C' | b'c' if s.len() > 1 && matches!(s.as_bytes()[1], b'<' | b'>') =>
+            {
+                // C<n: created less than n minutes ago; C>n: more than n minutes ago
+                let ts = minutes_ago(&s[2..])?;
+                if s.as_bytes()[1] == b'<' { Self::CreatedAfter(ts) } else { Self::CreatedBefore(ts) }
+            }
+            b'T' | b't' if s.len() > 1 && matches!(s.as_bytes()[1], b'<' | b'>') =>
+            {
+                let ts = minutes_ago(&s[2..])?;
+                if s.as_bytes()[1] == b'<' { Self::TopicAfter(ts) } else { Self::TopicBefore(ts) }
+            }
+            _ => Self::Mask(Pattern::new(s.to_ascii_lowercase())),
+        })
+    }
+
+    fn matches(&self, channel: &wrapper::Channel, users: usize) -> bool
+    {
+        let name = channel.name().value().to_ascii_lowercase();
+        let topic_ts = channel.topic().map(|t| t.timestamp());
+        // Channels which existed before creation times were recorded match neither C< nor C>
+        let created = Some(channel.created()).filter(|ts| *ts != 0);
+
+        match self
+        {
+            Self::Mask(mask) => mask.matches(&name),
+            Self::NotMask(mask) => !mask.matches(&name),
+            Self::MoreUsers(n) => users > *n,
+            Self::FewerUsers(n) => users < *n,
+            Self::CreatedBefore(ts) => created.map(|c| c < *ts).unwrap_or(false),
+            Self::CreatedAfter(ts) => created.map(|c| c > *ts).unwrap_or(false),
+            Self::TopicBefore(ts) => topic_ts.map(|t| t < *ts).unwrap_or(false),
+            Self::TopicAfter(ts) => topic_ts.map(|t| t > *ts).unwrap_or(false),
+        }
+    }
+}
+
+#[command_handler("LIST")]
+fn handle_list(server: &ClientServer, net: &Network, source: UserSource, cmd: &dyn Command,
+               filters: Option<&str>) -> CommandResult
+{
+    let now = sable_network::utils::now();
+
+    let mut masks = Vec::new();
+    let mut conditions = Vec::new();
+
+    for filter in filters.unwrap_or("").split(',').filter(|f| !f.is_empty())
+    {
+        match ListFilter::parse(filter, now)
+        {
+            Some(mask @ ListFilter::Mask(_)) => masks.push(mask),
+            Some(condition) => conditions.push(condition),
+            None =>
+            {
+                // Listing everything would be surprising for a query meant to narrow the list
+                cmd.notice(format_args!("Invalid LIST filter: {}", filter));
+                cmd.numeric(make_numeric!(EndOfList));
+                return Ok(());
+            }
+        }
+    }
+
+    for channel in net.channels()
+    {
+        if channel.mode().has_mode(ChannelModeFlag::Secret)
+            && source.is_in_channel(channel.id()).is_none()
+            && server.policy().can_see_secret(&source).is_err()
+        {
+            continue;
+        }
+
+        let users = channel.members().count();
+
+        // A channel is listed if it matches any of the given masks, and all of the other conditions
+        if !masks.is_empty() && !masks.iter().any(|m| m.matches(&channel, users))
+        {
+            continue;
+        }
+        if !conditions.iter().all(|c| c.matches(&channel, users))
+        {
+            continue;
+        }
+
+        let topic = channel.topic().map(|t| t.text().to_owned()).unwrap_or_default();
+        cmd.numeric(make_numeric!(ListEntry, &channel, users, &topic));
+    }
+
+    cmd.numeric(make_numeric!(EndOfList));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    const NOW: i64 = 100_000;
+
+    #[test]
+    fn user_counts()
+    {
+        assert_eq!(ListFilter::parse("<5", NOW), Some(ListFilter::FewerUsers(5)));
+        assert_eq!(ListFilter::parse("<0", NOW), Some(ListFilter::FewerUsers(0)));
+        assert_eq!(ListFilter::parse(">3", NOW), Some(ListFilter::MoreUsers(3)));
+        assert_eq!(ListFilter::parse("<", NOW), None);
+        assert_eq!(ListFilter::parse(">x", NOW), None);
+        assert_eq!(ListFilter::parse("<-1", NOW), None);
+    }
+
+    #[test]
+    fn creation_times()
+    {
+        assert_eq!(ListFilter::parse("C<10", NOW), Some(ListFilter::CreatedAfter(NOW - 600)));
+        assert_eq!(ListFilter::parse("c>10", NOW), Some(ListFilter::CreatedBefore(NOW - 600)));
+        assert_eq!(ListFilter::parse("C<", NOW), None);
+        assert_eq!(ListFilter::parse("C>ten", NOW), None);
+    }
+
+    #[test]
+    fn topic_times()
+    {
+        assert_eq!(ListFilter::parse("T>5", NOW), Some(ListFilter::TopicBefore(NOW - 300)));
+        assert_eq!(ListFilter::parse("t<5", NOW), Some(ListFilter::TopicAfter(NOW - 300)));
+        assert_eq!(ListFilter::parse("T>", NOW), None);
+    }
+
+    #[test]
+    fn masks()
+    {
+        assert_eq!(ListFilter::parse("#Foo*", NOW), Some(ListFilter::Mask(Pattern::new("#foo*".to_owned()))));
+        assert_eq!(ListFilter::parse("!#foo*", NOW), Some(ListFilter::NotMask(Pattern::new("#foo*".to_owned()))));
+        // Anything not starting with a filter prefix is a mask, even if it resembles one
+        assert_eq!(ListFilter::parse("Cfoo", NOW), Some(ListFilter::Mask(Pattern::new("cfoo".to_owned()))));
+    }
+}
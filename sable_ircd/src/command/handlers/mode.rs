@@ -153,7 +153,7 @@ fn handle_channel_mode(server: &ClientServer, source: &wrapper::User, cmd: &dyn
                             let detail = event::NewListModeEntry {
                                 list: list.id(),
                                 pattern: Pattern::new(mask.to_owned()),
-                                setter: source.id()
+                                setter: source.id().into()
                             };
                             server.add_action(CommandAction::state_change(server.ids().next_list_mode_entry(), detail));
                         }
@@ -0,0 +1,136 @@
+use super::*;
+use crate::client::MONITOR_LIMIT;
+
+/// Maximum length of the target list in a single MONITOR reply
+const CONTENT_LEN: usize = 300;
+
+#[command_handler("MONITOR")]
+fn handle_monitor(net: &Network, _source: UserSource, cmd: &dyn Command,
+                  subcommand: &str, targets: Option<&str>) -> CommandResult
+{
+    let conn = cmd.connection();
+
+    match subcommand
+    {
+        "+" =>
+        {
+            let targets = targets.ok_or_else(|| make_numeric!(NotEnoughParameters, "MONITOR"))?;
+
+            let mut online = Vec::new();
+            let mut offline = Vec::new();
+
+            let mut iter = targets.split(',').filter(|t| !t.is_empty());
+            while let Some(target) = iter.next()
+            {
+                let nick = match Nickname::convert(target)
+                {
+                    Ok(nick) => nick,
+                    Err(_) => continue
+                };
+
+                if !conn.add_monitor(nick)
+                {
+                    // The list is full; report this and every target we didn't get to
+                    let remaining: Vec<_> = std::iter::once(target).chain(iter).collect();
+                    cmd.numeric(make_numeric!(MonListFull, MONITOR_LIMIT, &remaining.join(",")));
+                    break;
+                }
+
+                match net.user_by_nick(&nick)
+                {
+                    Ok(user) => online.push(<wrapper::User as messages::MessageSource>::format(&user)),
+                    Err(_) => offline.push(nick.to_string()),
+                }
+            }
+
+            for line in join_targets(online)
+            {
+                cmd.numeric(make_numeric!(MonOnline, &line));
+            }
+            for line in join_targets(offline)
+            {
+                cmd.numeric(make_numeric!(MonOffline, &line));
+            }
+        }
+        "-" =>
+        {
+            let targets = targets.ok_or_else(|| make_numeric!(NotEnoughParameters, "MONITOR"))?;
+
+            for target in targets.split(',')
+            {
+                if let Ok(nick) = Nickname::convert(target)
+                {
+                    conn.remove_monitor(&nick);
+                }
+            }
+        }
+        "C" | "c" =>
+        {
+            conn.clear_monitors();
+        }
+        "L" | "l" =>
+        {
+            for line in join_targets(conn.monitors().iter().map(ToString::to_string).collect())
+            {
+                cmd.numeric(make_numeric!(MonList, &line));
+            }
+            cmd.numeric(make_numeric!(EndOfMonList));
+        }
+        "S" | "s" =>
+        {
+            let mut online = Vec::new();
+            let mut offline = Vec::new();
+
+            for nick in conn.monitors()
+            {
+                match net.user_by_nick(&nick)
+                {
+                    Ok(user) => online.push(<wrapper::User as messages::MessageSource>::format(&user)),
+                    Err(_) => offline.push(nick.to_string()),
+                }
+            }
+
+            for line in join_targets(online)
+            {
+                cmd.numeric(make_numeric!(MonOnline, &line));
+            }
+            for line in join_targets(offline)
+            {
+                cmd.numeric(make_numeric!(MonOffline, &line));
+            }
+        }
+        _ =>
+        {
+            return Err(CommandError::InvalidArgument(subcommand.to_string(), "MONITOR subcommand".to_string()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Join a list of targets with commas, split into lines no longer than [`CONTENT_LEN`]
+fn join_targets(targets: Vec<String>) -> Vec<String>
+{
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for target in targets
+    {
+        if !current.is_empty() && current.len() + target.len() + 1 > CONTENT_LEN
+        {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty()
+        {
+            current.push(',');
+        }
+        current.push_str(&target);
+    }
+
+    if !current.is_empty()
+    {
+        lines.push(current);
+    }
+
+    lines
+}
@@ -1,7 +1,7 @@
 use super::*;
 
 #[command_handler("NOTICE")]
-fn handle_notice(server: &ClientServer, source: UserSource,
+fn handle_notice(server: &ClientServer, source: UserSource, cmd: &dyn Command,
                  target: TargetParameter, msg: &str) -> CommandResult
 {
     if let Some(user) = target.user()
@@ -22,6 +22,7 @@ fn handle_notice(server: &ClientServer, source: UserSource,
         target: target.object_id(),
         message_type: state::MessageType::Notice,
         text: msg.to_owned(),
+        tags: cmd.client_only_tags(),
     };
     server.add_action(CommandAction::state_change(server.ids().next_message(), details));
     Ok(())
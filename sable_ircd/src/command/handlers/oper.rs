@@ -6,40 +6,61 @@ use state::{
 };
 
 #[command_handler("OPER")]
-fn handle_oper(server: &ClientServer, net: &Network, source: UserSource,
+fn handle_oper(server: &ClientServer, net: &Network, source: UserSource, cmd: &dyn Command,
                oper_name: &str, password: &str) -> CommandResult
 {
     server.policy().user_can_oper(&source)?;
 
-    if let Some(conf) = find_oper_block(net, &source, oper_name)
-    {
-        if server.policy().authenticate(conf, oper_name, password)
-        {
-            let audit = details::NewAuditLogEntry {
-                category: AuditLogCategory::General,
-                fields: vec![
-                    (AuditLogField::Source, source.nuh()),
-                    (AuditLogField::ActionType, "OPER".to_string()),
-                ]
-            };
-            server.add_action(CommandAction::state_change(server.ids().next_audit_log_entry(), audit));
-
-            server.add_action(CommandAction::state_change(source.id(), details::OperUp {
-                oper_name: oper_name.to_owned()
-            }));
-            Ok(())
-        }
-        else
-        {
-            numeric_error!(NoOperConf)
-        }
-    }
-    else
+    let Some(conf) = find_oper_block(net, &source, oper_name) else {
+        audit_failure(server, &source, oper_name, "No such oper block");
+        return numeric_error!(NoOperConf);
+    };
+
+    let connection = cmd.connection();
+    let fingerprint = connection.tls_info().and_then(|ti| ti.fingerprint.as_deref());
+
+    if let Err(failure) = server.policy().authenticate(conf, &source, oper_name, password, fingerprint)
     {
-        numeric_error!(NoOperConf)
+        audit_failure(server, &source, oper_name, &failure.to_string());
+        return numeric_error!(NoOperConf);
     }
+
+    // An oper block with no class, or naming a class which doesn't exist, is a configuration
+    // error; refuse it rather than granting an arbitrary set of privileges
+    let Some(class) = conf.class.as_deref().and_then(|class| net.config().oper_class(class)) else {
+        audit_failure(server, &source, oper_name, "No valid oper class");
+        return numeric_error!(NoOperConf);
+    };
+
+    let audit = details::NewAuditLogEntry {
+        category: AuditLogCategory::General,
+        fields: vec![
+            (AuditLogField::Source, source.nuh()),
+            (AuditLogField::ActionType, "OPER".to_string()),
+        ]
+    };
+    server.add_action(CommandAction::state_change(server.ids().next_audit_log_entry(), audit));
+
+    server.add_action(CommandAction::state_change(source.id(), details::OperUp {
+        oper_name: oper_name.to_owned(),
+        privileges: class.privileges,
+    }));
+    Ok(())
 }
 
+/// Record a failed attempt to oper up
+fn audit_failure(server: &ClientServer, source: &wrapper::User, oper_name: &str, reason: &str)
+{
+    let audit = details::NewAuditLogEntry {
+        category: AuditLogCategory::General,
+        fields: vec![
+            (AuditLogField::Source, source.nuh()),
+            (AuditLogField::ActionType, "OPER_FAILED".to_string()),
+            (AuditLogField::Reason, format!("{}: {}", oper_name, reason)),
+        ]
+    };
+    server.add_action(CommandAction::state_change(server.ids().next_audit_log_entry(), audit));
+}
 
 fn find_oper_block<'a>(net: &'a Network, _user: &wrapper::User, oper_name: &str) -> Option<&'a config::OperConfig>
 {
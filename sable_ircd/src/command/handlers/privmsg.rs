@@ -10,6 +10,11 @@ async fn handle_privmsg(server: &ClientServer, source: UserSource<'_>, cmd: &dyn
         {
             return super::services::dispatch_alias_command(cmd, &user, &alias.command_alias, msg).await;
         }
+
+        if let Some(reason) = user.away_reason()
+        {
+            cmd.numeric(make_numeric!(Away, &user, reason));
+        }
     }
 
     if let Some(channel) = target.channel()
@@ -22,6 +27,7 @@ async fn handle_privmsg(server: &ClientServer, source: UserSource<'_>, cmd: &dyn
         target: target.object_id(),
         message_type: state::MessageType::Privmsg,
         text: msg.to_owned(),
+        tags: cmd.client_only_tags(),
     };
     server.add_action(CommandAction::state_change(server.ids().next_message(), details));
     Ok(())
@@ -3,23 +3,6 @@ use super::*;
 #[command_handler("REGISTER")]
 pub async fn handle_register(network: &Network, source: CommandSource<'_>, cmd: &dyn Command,
                          account: &str, email: &str, password: &str) -> CommandResult
-{
-    match source
-    {
-        CommandSource::PreClient(_) =>
-        {
-            cmd.response(&message::Fail::new("REGISTER",
-                                             "COMPLETE_CONNECTION_REQUIRED",
-                                             "*",
-                                             "Finish connecting before registering"));
-            Ok(())
-        }
-        CommandSource::User(user) => do_register_user(network, user, cmd, account, email, password).await
-    }
-}
-
-async fn do_register_user(network: &Network, source: wrapper::User<'_>, cmd: &dyn Command,
-                    account: &str, _email: &str, password: &str) -> CommandResult
 {
     let Some(services_name) = network.current_services_name() else {
         cmd.response(&message::Fail::new("REGISTER",
@@ -29,15 +12,46 @@ async fn do_register_user(network: &Network, source: wrapper::User<'_>, cmd: &dy
         return Ok(())
     };
 
-    let requested_account = if account == "*" { source.nick() } else { Nickname::from_str(account)? };
+    let (current_nick, already_logged_in) = match &source
+    {
+        CommandSource::PreClient(pc) => (pc.nick.get().copied(), pc.sasl_account.get().is_some()),
+        CommandSource::User(user) => (Some(user.nick()), user.account()?.is_some()),
+    };
+
+    if already_logged_in
+    {
+        cmd.response(&message::Fail::new("REGISTER",
+                                                "ALREADY_AUTHENTICATED",
+                                                account,
+                                                "You are already logged in"));
+        return Ok(())
+    }
+
+    let requested_account = if account == "*"
+    {
+        let Some(nick) = current_nick else {
+            cmd.response(&message::Fail::new("REGISTER",
+                                                    "NEED_NICK",
+                                                    "*",
+                                                    "You must choose a nickname or give an account name"));
+            return Ok(())
+        };
+        nick
+    }
+    else
+    {
+        Nickname::from_str(account)?
+    };
 
-    if requested_account != source.nick()
+    // Registering an account also registers its name as a nick, so don't allow taking a
+    // name which someone else is currently using
+    let source_user = match &source { CommandSource::User(user) => Some(user.id()), CommandSource::PreClient(_) => None };
+    if matches!(network.user_by_nick(&requested_account), Ok(user) if Some(user.id()) != source_user)
     {
-        // We don't support registering with an account other than your current nick (yet?)
         cmd.response(&message::Fail::new("REGISTER",
-                                            "ACCOUNT_NAME_MUST_BE_NICK",
-                                            account,
-                                            "Your account name must be your current nickname"));
+                                                "BAD_ACCOUNT_NAME",
+                                                requested_account.value().as_str(),
+                                                "That name is in use by another user"));
         return Ok(())
     }
 
@@ -50,17 +64,28 @@ async fn do_register_user(network: &Network, source: wrapper::User<'_>, cmd: &dy
         return Ok(())
     }
 
-    let message = rpc::RemoteServerRequestType::RegisterUser(requested_account, password.to_owned());
+    let email = if email == "*" { None } else { Some(email.to_owned()) };
+    let message = rpc::RemoteServerRequestType::RegisterUser(requested_account, password.to_owned(), email);
 
     match cmd.server().node().sync_log().send_remote_request(services_name, message).await
     {
         Ok(rpc::RemoteServerResponse::LogUserIn(account)) =>
         {
-            cmd.server().add_action(CommandAction::state_change(source.id(), event::UserLogin {
-                account: Some(account)
-            }));
+            log_in_source(cmd, &source, account);
             cmd.response(&message::Register::new("SUCCESS", requested_account, "You have successfully registered"));
         }
+        Ok(rpc::RemoteServerResponse::VerificationRequired) =>
+        {
+            cmd.response(&message::Register::new("VERIFICATION_REQUIRED", requested_account,
+                                                 "A verification code has been sent to your email address; use VERIFY to complete registration"));
+        }
+        Ok(rpc::RemoteServerResponse::InvalidEmail) =>
+        {
+            cmd.response(&message::Fail::new("REGISTER",
+                                                    "INVALID_EMAIL",
+                                                    account,
+                                                    "A valid email address is required"));
+        }
         Ok(rpc::RemoteServerResponse::AlreadyExists) =>
         {
             cmd.response(&message::Fail::new("REGISTER",
@@ -88,3 +113,31 @@ async fn do_register_user(network: &Network, source: wrapper::User<'_>, cmd: &dy
 
     Ok(())
 }
+
+/// Log the source of a command in to a newly registered account. A pre-client is logged in
+/// when it finishes connecting.
+pub(super) fn log_in_source(cmd: &dyn Command, source: &CommandSource, account: AccountId)
+{
+    match source
+    {
+        CommandSource::User(user) =>
+        {
+            cmd.server().add_action(CommandAction::state_change(user.id(), event::UserLogin {
+                account: Some(account)
+            }));
+        }
+        CommandSource::PreClient(pc) =>
+        {
+            pc.sasl_account.set(account).ok();
+
+            // The connection may have finished registering while we waited for services, in
+            // which case it's too late for the pre-client's account to be picked up
+            if let Some(user_id) = cmd.connection().user_id()
+            {
+                cmd.server().add_action(CommandAction::state_change(user_id, event::UserLogin {
+                    account: Some(account)
+                }));
+            }
+        }
+    }
+}
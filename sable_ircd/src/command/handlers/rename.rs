@@ -0,0 +1,33 @@
+use super::*;
+
+#[command_handler("RENAME")]
+fn handle_rename(server: &ClientServer, net: &Network, source: UserSource, cmd: &dyn Command,
+                 channel: wrapper::Channel, new_name: &str, reason: Option<&str>) -> CommandResult
+{
+    let new_name = ChannelName::from_str(new_name)?;
+    let context = format!("{} {}", channel.name(), new_name);
+
+    // The channel type is determined by its prefix, so that can't change
+    if channel.name().value().chars().next() != new_name.value().chars().next()
+    {
+        cmd.response(&message::Fail::new("RENAME", "CANNOT_RENAME", &context, "You cannot change a channel's type"));
+        return Ok(());
+    }
+
+    if matches!(net.channel_by_name(&new_name), Ok(existing) if existing.id() != channel.id())
+    {
+        cmd.response(&message::Fail::new("RENAME", "CHANNEL_NAME_IN_USE", &context, "Channel name is already in use"));
+        return Ok(());
+    }
+
+    server.policy().can_rename(&source, &channel, &new_name)?;
+
+    let details = event::ChannelRename {
+        source: source.id().into(),
+        new_name,
+        message: reason.unwrap_or("").to_owned(),
+    };
+    server.add_action(CommandAction::state_change(channel.id(), details));
+
+    Ok(())
+}
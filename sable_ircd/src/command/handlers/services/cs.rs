@@ -17,4 +17,5 @@ async fn handle_cs<'a>(_source: UserSource<'a>, subcommand: &'a str, args: ArgLi
 
 mod register;
 mod access;
-mod role;
\ No newline at end of file
+mod role;
+mod set;
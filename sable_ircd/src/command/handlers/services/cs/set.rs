@@ -0,0 +1,142 @@
+use sable_network::{
+    rpc::{RemoteServerResponse, RemoteServerRequestType, ChannelSettingChange},
+    network::state::{ChannelModeLock, PersistentListEntry},
+    policy::RegistrationPolicyService,
+};
+use strum::IntoEnumIterator;
+
+use super::*;
+
+const SET_SYNTAX: &str = "Syntax: CS SET <#channel> [TOPICLOCK <topic>|OFF | MLOCK <modes> [key]|OFF | ENTRYMSG <message>|OFF | LISTS SAVE|CLEAR]";
+
+#[command_handler("SET", in("CS"))]
+async fn handle_set(source: LoggedInUserSource<'_>, cmd: &dyn Command, services_target: ServicesTarget<'_>, net: &Network,
+                    channel: wrapper::ChannelRegistration<'_>, setting: &str, mut args: ArgList<'_>) -> CommandResult
+{
+    cmd.server().node().policy().can_change_settings(&source.account, &channel)?;
+
+    let mut words = Vec::new();
+    while let Ok(word) = args.next::<&str>()
+    {
+        words.push(word);
+    }
+    let value = words.join(" ");
+    let clearing = value.eq_ignore_ascii_case("OFF");
+
+    let change = match setting.to_ascii_uppercase().as_str()
+    {
+        "TOPICLOCK" if !value.is_empty() =>
+        {
+            ChannelSettingChange::TopicLock((!clearing).then_some(value))
+        }
+        "ENTRYMSG" if !value.is_empty() =>
+        {
+            ChannelSettingChange::EntryMessage((!clearing).then_some(value))
+        }
+        "MLOCK" if clearing =>
+        {
+            ChannelSettingChange::ModeLock(None)
+        }
+        "MLOCK" if !words.is_empty() =>
+        {
+            let Some(mode_lock) = parse_mode_lock(cmd, words[0], words.get(1).copied()) else { return Ok(()) };
+            ChannelSettingChange::ModeLock(Some(mode_lock))
+        }
+        "LISTS" if value.eq_ignore_ascii_case("CLEAR") =>
+        {
+            ChannelSettingChange::ListEntries(Vec::new())
+        }
+        "LISTS" if value.eq_ignore_ascii_case("SAVE") =>
+        {
+            let Ok(live_channel) = net.channel_by_name(channel.name()) else {
+                cmd.notice(format_args!("Channel {} doesn't currently exist", channel.name()));
+                return Ok(());
+            };
+
+            let entries = ListModeType::iter().flat_map(|list_type| {
+                live_channel.list(list_type).entries().map(move |entry| PersistentListEntry {
+                    list_type,
+                    pattern: entry.pattern().clone()
+                }).collect::<Vec<_>>()
+            }).collect();
+
+            ChannelSettingChange::ListEntries(entries)
+        }
+        _ =>
+        {
+            cmd.notice(SET_SYNTAX);
+            return Ok(());
+        }
+    };
+
+    let request = RemoteServerRequestType::ModifyChannelSettings { source: source.account.id(), channel: channel.id(), change };
+    let registration_response = services_target.send_remote_request(request).await;
+
+    tracing::debug!(?registration_response, "Got registration response");
+    match registration_response
+    {
+        Ok(RemoteServerResponse::Success) =>
+        {
+            cmd.notice(format_args!("Settings for {} successfully updated", channel.name()));
+        }
+        Ok(RemoteServerResponse::AccessDenied) =>
+        {
+            cmd.notice("Access denied");
+        }
+        Ok(response) =>
+        {
+            tracing::error!(?response, "Unexpected response updating channel settings");
+            cmd.notice("Error updating channel settings");
+        }
+        Err(error) =>
+        {
+            tracing::error!(?error, "Error updating channel settings");
+            cmd.notice("Error updating channel settings");
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse a mode string such as `+nt-s` into a mode lock. A key is required if and only
+/// if the mode string adds `+k`.
+fn parse_mode_lock(cmd: &dyn Command, modes: &str, key: Option<&str>) -> Option<ChannelModeLock>
+{
+    let mut mode_lock = ChannelModeLock { added: ChannelModeSet::new(), removed: ChannelModeSet::new(), key: None };
+    let mut adding = true;
+
+    for c in modes.chars()
+    {
+        match c
+        {
+            '+' => adding = true,
+            '-' => adding = false,
+            'k' if adding =>
+            {
+                let Some(key) = key.and_then(|k| ChannelKey::from_str(k).ok()) else {
+                    cmd.notice("A valid key must be given when locking mode +k");
+                    return None;
+                };
+                mode_lock.key = Some(key);
+            }
+            _ =>
+            {
+                let Some(flag) = ChannelModeSet::flag_for(c) else {
+                    cmd.notice(format_args!("Unknown or unlockable channel mode {}", c));
+                    return None;
+                };
+
+                if adding
+                {
+                    mode_lock.added |= flag;
+                }
+                else
+                {
+                    mode_lock.removed |= flag;
+                }
+            }
+        }
+    }
+
+    Some(mode_lock)
+}
@@ -32,6 +32,7 @@ impl<'a> Command for ServicesCommand<'a>
     fn response(&self, message: &dyn messages::MessageTypeFormat) { self.outer.response(message) }
     fn connection_id(&self) -> client_listener::ConnectionId { self.outer.connection_id() }
     fn connection(&self) -> &ClientConnection { self.outer.connection() }
+    fn tags(&self) -> &[state::ClientTag] { self.outer.tags() }
 
     fn response_source(&self) -> &dyn messages::MessageSource
     {
@@ -15,5 +15,21 @@ async fn handle_cs<'a>(_source: UserSource<'a>, subcommand: &'a str, args: ArgLi
     Ok(())
 }
 
+/// Collect the remaining arguments of a command which takes a new password, so that extra
+/// words are passed on to services to be rejected rather than silently dropped
+fn new_password_arg(mut args: ArgList<'_>) -> String
+{
+    let mut words = Vec::new();
+    while let Ok(word) = args.next::<&str>()
+    {
+        words.push(word);
+    }
+    words.join(" ")
+}
+
 mod login;
-mod cert;
\ No newline at end of file
+mod cert;
+mod set;
+mod drop;
+mod recover;
+mod group;
\ No newline at end of file
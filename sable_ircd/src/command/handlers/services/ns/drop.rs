@@ -0,0 +1,35 @@
+use sable_network::rpc::{RemoteServerResponse, RemoteServerRequestType};
+
+use super::*;
+
+#[command_handler("DROP", in("NS"))]
+async fn handle_drop(services: ServicesTarget<'_>, source: LoggedInUserSource<'_>, cmd: &dyn Command,
+                     password: &str) -> CommandResult
+{
+    let account_name = source.account.name();
+    let req = RemoteServerRequestType::DropAccount(source.account.id(), password.to_owned());
+
+    match services.send_remote_request(req).await
+    {
+        Ok(RemoteServerResponse::Success) =>
+        {
+            cmd.notice(format_args!("Account {} has been dropped", account_name));
+        }
+        Ok(RemoteServerResponse::InvalidCredentials) =>
+        {
+            cmd.notice(format_args!("Invalid credentials for {}", account_name));
+        }
+        Ok(response) =>
+        {
+            tracing::warn!(?response, "Unexpected response to drop account message");
+            cmd.notice("Error dropping account");
+        }
+        Err(e) =>
+        {
+            tracing::warn!(?e, "Error response dropping account");
+            cmd.notice("Error dropping account");
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use sable_network::rpc::{RemoteServerResponse, RemoteServerRequestType};
+
+use super::*;
+
+#[command_handler("GROUP", in("NS"))]
+async fn handle_group(services: ServicesTarget<'_>, source: LoggedInUserSource<'_>, cmd: &dyn Command) -> CommandResult
+{
+    let nick = source.user.nick();
+    let req = RemoteServerRequestType::GroupNick(source.account.id(), nick);
+
+    match services.send_remote_request(req).await
+    {
+        Ok(RemoteServerResponse::Success) =>
+        {
+            cmd.notice(format_args!("Nickname {} is now registered to account {}", nick, source.account.name()));
+        }
+        Ok(RemoteServerResponse::AlreadyExists) =>
+        {
+            cmd.notice(format_args!("Nickname {} is already registered", nick));
+        }
+        Ok(response) =>
+        {
+            tracing::warn!(?response, "Unexpected response to group nick message");
+            cmd.notice("Error registering nickname");
+        }
+        Err(e) =>
+        {
+            tracing::warn!(?e, "Error response grouping nick");
+            cmd.notice("Error registering nickname");
+        }
+    }
+
+    Ok(())
+}
+
+#[command_handler("UNGROUP", in("NS"))]
+async fn handle_ungroup(services: ServicesTarget<'_>, source: LoggedInUserSource<'_>, cmd: &dyn Command,
+                        nick: Option<Nickname>) -> CommandResult
+{
+    let nick = nick.unwrap_or_else(|| source.user.nick());
+    let req = RemoteServerRequestType::UngroupNick(source.account.id(), nick);
+
+    match services.send_remote_request(req).await
+    {
+        Ok(RemoteServerResponse::Success) =>
+        {
+            cmd.notice(format_args!("Nickname {} has been removed from account {}", nick, source.account.name()));
+        }
+        Ok(RemoteServerResponse::NoAccount) =>
+        {
+            cmd.notice(format_args!("Nickname {} is not registered", nick));
+        }
+        Ok(RemoteServerResponse::AccessDenied) =>
+        {
+            cmd.notice(format_args!("Nickname {} can't be removed from your account", nick));
+        }
+        Ok(response) =>
+        {
+            tracing::warn!(?response, "Unexpected response to ungroup nick message");
+            cmd.notice("Error removing nickname");
+        }
+        Err(e) =>
+        {
+            tracing::warn!(?e, "Error response ungrouping nick");
+            cmd.notice("Error removing nickname");
+        }
+    }
+
+    Ok(())
+}
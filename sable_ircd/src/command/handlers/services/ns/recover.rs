@@ -0,0 +1,77 @@
+use sable_network::rpc::{RemoteServerResponse, RemoteServerRequestType};
+
+use super::*;
+
+/// Generate a password recovery token for an account. The token is shown to the
+/// requesting oper, who is responsible for passing it on to the account owner.
+#[command_handler("RESETPASS", in("NS"))]
+async fn handle_resetpass(server: &ClientServer, services: ServicesTarget<'_>, source: UserSource<'_>, cmd: &dyn Command,
+                          account: wrapper::Account<'_>) -> CommandResult
+{
+    server.policy().require_oper(&source)?;
+    server.policy().can_administer_services(&source)?;
+
+    let req = RemoteServerRequestType::BeginPasswordRecovery(account.id());
+
+    match services.send_remote_request(req).await
+    {
+        Ok(RemoteServerResponse::RecoveryToken(token)) =>
+        {
+            cmd.notice(format_args!("Recovery token for {} is {}. It is valid for one hour; use SETPASS {} {} <new password>",
+                                    account.name(), token, account.name(), token));
+        }
+        Ok(response) =>
+        {
+            tracing::warn!(?response, "Unexpected response to password recovery message");
+            cmd.notice("Error generating recovery token");
+        }
+        Err(e) =>
+        {
+            tracing::warn!(?e, "Error response generating recovery token");
+            cmd.notice("Error generating recovery token");
+        }
+    }
+
+    Ok(())
+}
+
+#[command_handler("SETPASS", in("NS"))]
+async fn handle_setpass(services: ServicesTarget<'_>, cmd: &dyn Command,
+                        account: wrapper::Account<'_>, token: &str, args: ArgList<'_>) -> CommandResult
+{
+    let password = new_password_arg(args);
+    if password.is_empty()
+    {
+        return Err(CommandError::NotEnoughParameters);
+    }
+
+    let req = RemoteServerRequestType::RecoverPassword(account.id(), token.to_owned(), password);
+
+    match services.send_remote_request(req).await
+    {
+        Ok(RemoteServerResponse::Success) =>
+        {
+            cmd.notice(format_args!("The password for {} has been changed", account.name()));
+        }
+        Ok(RemoteServerResponse::InvalidCredentials) =>
+        {
+            cmd.notice(format_args!("Invalid or expired recovery token for {}", account.name()));
+        }
+        Ok(RemoteServerResponse::Error(message)) =>
+        {
+            cmd.notice(message);
+        }
+        Ok(response) =>
+        {
+            tracing::warn!(?response, "Unexpected response to password recovery message");
+            cmd.notice("Error changing password");
+        }
+        Err(e) =>
+        {
+            tracing::warn!(?e, "Error response changing password");
+            cmd.notice("Error changing password");
+        }
+    }
+
+    Ok(())
+}
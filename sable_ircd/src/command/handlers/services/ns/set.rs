@@ -0,0 +1,48 @@
+use sable_network::rpc::{RemoteServerResponse, RemoteServerRequestType};
+
+use super::*;
+
+#[command_handler("SET", in("NS"))]
+async fn handle_set(services: ServicesTarget<'_>, source: LoggedInUserSource<'_>, cmd: &dyn Command,
+                    setting: &str, args: ArgList<'_>) -> CommandResult
+{
+    let value = new_password_arg(args);
+
+    match setting.to_ascii_uppercase().as_str()
+    {
+        "PASSWORD" if !value.is_empty() => set_password(services, source, cmd, &value).await,
+        _ => {
+            cmd.notice("Invalid setting. Syntax: SET PASSWORD <new password>");
+            Ok(())
+        }
+    }
+}
+
+async fn set_password(services: ServicesTarget<'_>, source: LoggedInUserSource<'_>, cmd: &dyn Command, password: &str) -> CommandResult
+{
+    let req = RemoteServerRequestType::SetPassword(source.account.id(), password.to_owned());
+
+    match services.send_remote_request(req).await
+    {
+        Ok(RemoteServerResponse::Success) =>
+        {
+            cmd.notice(format_args!("The password for {} has been changed", source.account.name()));
+        }
+        Ok(RemoteServerResponse::Error(message)) =>
+        {
+            cmd.notice(message);
+        }
+        Ok(response) =>
+        {
+            tracing::warn!(?response, "Unexpected response to set password message");
+            cmd.notice("Error changing password");
+        }
+        Err(e) =>
+        {
+            tracing::warn!(?e, "Error response changing password");
+            cmd.notice("Error changing password");
+        }
+    }
+
+    Ok(())
+}
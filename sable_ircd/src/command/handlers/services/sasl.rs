@@ -8,6 +8,20 @@ async fn handle_authenticate(source: PreClientSource, net: &Network, cmd: &dyn C
                              server: &ClientServer, services: Conditional<ServicesTarget<'_>>,
                              text: &str) -> CommandResult
 {
+    let services = match services.require()
+    {
+        Ok(services) => services,
+        Err(e) =>
+        {
+            // If services isn't available, we can still process EXTERNAL locally
+            if source.sasl_session.get().is_none() && text == "EXTERNAL"
+            {
+                return do_sasl_external(source, net, cmd);
+            }
+            return Err(e);
+        }
+    };
+
     let authenticate_request = if let Some(session) = source.sasl_session.get()
     {
         // A session already exists, so the argument is "*" or base64-encoded session data
@@ -15,6 +29,11 @@ async fn handle_authenticate(source: PreClientSource, net: &Network, cmd: &dyn C
         {
             RemoteServerRequestType::AbortAuthenticate(*session)
         }
+        else if text == "+"
+        {
+            // An empty response, e.g. EXTERNAL without an authorisation identity
+            RemoteServerRequestType::Authenticate(*session, Vec::new())
+        }
         else
         {
             let Ok(data) = BASE64_STANDARD.decode(text) else {
@@ -28,22 +47,16 @@ async fn handle_authenticate(source: PreClientSource, net: &Network, cmd: &dyn C
     else
     {
         // No session, so the argument is the mechanism name
-
-        // Special case for EXTERNAL, which we can handle without going to services
-        if text == "EXTERNAL"
-        {
-            return do_sasl_external(source, net, cmd);
-        }
-
         let mechanism = text.to_owned();
+        let fingerprint = cmd.connection().tls_info().and_then(|ti| ti.fingerprint.clone());
 
         let session = server.ids().next_sasl_session();
         source.sasl_session.set(session).ok();
 
-        RemoteServerRequestType::BeginAuthenticate(session, mechanism)
+        RemoteServerRequestType::BeginAuthenticate(session, mechanism, fingerprint)
     };
 
-    match services.require()?.send_remote_request(authenticate_request).await
+    match services.send_remote_request(authenticate_request).await
     {
         Ok(RemoteServerResponse::Authenticate(status)) =>
         {
@@ -0,0 +1,24 @@
+use super::*;
+
+/// Maximum length of a realname set via SETNAME
+const REALNAME_LEN: usize = 150;
+
+#[command_handler("SETNAME")]
+fn handle_setname(server: &ClientServer, source: UserSource, cmd: &dyn Command,
+                  realname: &str) -> CommandResult
+{
+    if realname.is_empty() || realname.len() > REALNAME_LEN
+    {
+        cmd.response(&message::Fail::new("SETNAME", "INVALID_REALNAME", "", "Realname is not valid"));
+        return Ok(());
+    }
+
+    let details = event::details::UserDetailsChange {
+        username: None,
+        visible_host: None,
+        realname: Some(realname.to_owned()),
+    };
+    server.add_action(CommandAction::state_change(source.id(), details));
+
+    Ok(())
+}
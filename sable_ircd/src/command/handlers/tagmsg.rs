@@ -0,0 +1,35 @@
+use super::*;
+
+#[command_handler("TAGMSG")]
+fn handle_tagmsg(server: &ClientServer, source: UserSource, cmd: &dyn Command,
+                 target: TargetParameter) -> CommandResult
+{
+    if let Some(user) = target.user()
+    {
+        if user.is_alias_user().is_some()
+        {
+            // Services aliases have no use for tag-only messages
+            return Ok(());
+        }
+    }
+    if let Some(channel) = target.channel()
+    {
+        server.policy().can_send(&source, channel, "")?;
+    }
+
+    let tags = cmd.client_only_tags();
+    if tags.is_empty()
+    {
+        return Ok(());
+    }
+
+    let details = event::details::NewMessage {
+        source: source.id(),
+        target: target.object_id(),
+        message_type: state::MessageType::Tagmsg,
+        text: String::new(),
+        tags,
+    };
+    server.add_action(CommandAction::state_change(server.ids().next_message(), details));
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use super::*;
+
+#[command_handler("VERIFY")]
+async fn handle_verify(network: &Network, source: CommandSource<'_>, cmd: &dyn Command,
+                       account: &str, code: &str) -> CommandResult
+{
+    let Some(services_name) = network.current_services_name() else {
+        cmd.response(&message::Fail::new("VERIFY",
+                                                "TEMPORARILY_UNAVAILABLE",
+                                                "*",
+                                                "Services are temporarily unavailable"));
+        return Ok(())
+    };
+
+    let account_name = Nickname::from_str(account)?;
+    let message = rpc::RemoteServerRequestType::VerifyAccount(account_name, code.to_owned());
+
+    match cmd.server().node().sync_log().send_remote_request(services_name, message).await
+    {
+        Ok(rpc::RemoteServerResponse::LogUserIn(account_id)) =>
+        {
+            super::register::log_in_source(cmd, &source, account_id);
+            cmd.response(&message::Verify::new("SUCCESS", account_name, "You have successfully registered"));
+        }
+        Ok(rpc::RemoteServerResponse::InvalidCredentials) =>
+        {
+            cmd.response(&message::Fail::new("VERIFY",
+                                                    "INVALID_CODE",
+                                                    account,
+                                                    "Invalid or expired verification code"));
+        }
+        Ok(rpc::RemoteServerResponse::AlreadyExists) =>
+        {
+            cmd.response(&message::Fail::new("VERIFY",
+                                                    "ACCOUNT_EXISTS",
+                                                    account,
+                                                    "Account already exists"));
+        }
+        Ok(response) =>
+        {
+            tracing::error!(?response, "Unexpected response from services");
+            cmd.response(&message::Fail::new("VERIFY",
+                                                    "TEMPORARILY_UNAVAILABLE",
+                                                    account,
+                                                    "Services are temporarily unavailable"));
+        }
+        Err(e) =>
+        {
+            tracing::error!(?e, "Error sending verify request");
+            cmd.response(&message::Fail::new("VERIFY",
+                                                    "TEMPORARILY_UNAVAILABLE",
+                                                    account,
+                                                    "Services are temporarily unavailable"));
+        }
+    }
+
+    Ok(())
+}
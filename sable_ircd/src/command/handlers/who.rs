@@ -1,29 +1,210 @@
 use super::*;
 use crate::utils::make_numeric;
 
+/// WHOX fields, in the order in which they must appear in a reply
+const WHOX_FIELDS: &str = "tcuihsnfdlaor";
+
+/// Field selection for a WHOX query, as given after the `%` in the options parameter
+struct WhoxQuery<'a>
+{
+    fields: String,
+    token: Option<&'a str>,
+}
+
+impl<'a> WhoxQuery<'a>
+{
+    /// Parse the `%fields[,token]` part of a WHO options parameter, if there is one
+    fn parse(options: &'a str) -> Option<Self>
+    {
+        let (_, whox) = options.split_once('%')?;
+        let (fields, token) = match whox.split_once(',')
+        {
+            Some((fields, token)) => (fields, Some(token)),
+            None => (whox, None)
+        };
+
+        // Replies list fields in a fixed order, whatever order they were requested in
+        let fields = WHOX_FIELDS.chars().filter(|c| fields.contains(*c)).collect();
+
+        Some(Self { fields, token })
+    }
+}
+
 #[command_handler("WHO")]
-fn handle_user(server: &ClientServer, cmd: &dyn Command, source: UserSource,
-               channel: wrapper::Channel) -> CommandResult
+fn handle_who(server: &ClientServer, net: &Network, cmd: &dyn Command, source: UserSource,
+              target: &str, options: Option<&str>) -> CommandResult
 {
-    for member in channel.members()
+    let whox = options.and_then(WhoxQuery::parse);
+    let opers_only = options.map(|o| o.split('%').next().unwrap_or("").contains('o')).unwrap_or(false);
+    // Opers with the relevant privilege can see secret channels' members and invisible users
+    let see_secret = server.policy().can_see_secret(&source).is_ok();
+
+    let channel = ChannelName::from_str(target).ok().and_then(|name| net.channel_by_name(&name).ok());
+
+    if let Some(channel) = channel
     {
-        if server.policy().can_see_user_on_channel(&source, &member).is_err()
+        for member in channel.members()
         {
-            continue;
+            if !see_secret && server.policy().can_see_user_on_channel(&source, &member).is_err()
+            {
+                continue;
+            }
+
+            let user = member.user()?;
+            if opers_only && !user.is_oper()
+            {
+                continue;
+            }
+
+            send_who_reply(cmd, &user, Some(&channel), Some(&member), whox.as_ref())?;
         }
+    }
+    else
+    {
+        // "0" and "*" are conventionally used to ask for every visible user
+        let mask = if target == "0" { "*" } else { target };
+        let pattern = Pattern::new(mask.to_ascii_lowercase());
+        let exact_nick = Nickname::from_str(target).ok();
+
+        for user in net.users()
+        {
+            if opers_only && !user.is_oper()
+            {
+                continue;
+            }
+
+            // An exact nickname query always finds its target; other masks only match users
+            // who are visible to the requester
+            if exact_nick != Some(user.nick())
+            {
+                if !see_secret && user.id() != source.id()
+                    && user.mode().has_mode(UserModeFlag::Invisible)
+                    && !user.channels().any(|m| source.is_in_channel(m.channel_id()).is_some())
+                {
+                    continue;
+                }
 
-        cmd.numeric(make_who_reply(&member.user()?, Some(&channel), Some(&member), &member.user()?.server()?));
+                if !user_matches(&user, &pattern)
+                {
+                    continue;
+                }
+            }
+
+            send_who_reply(cmd, &user, None, None, whox.as_ref())?;
+        }
     }
 
-    cmd.numeric(make_numeric!(EndOfWho, channel.name().value()));
+    cmd.numeric(make_numeric!(EndOfWho, target));
 
     Ok(())
 }
 
-fn make_who_reply(target: &wrapper::User, channel: Option<&wrapper::Channel>,
-                    membership: Option<&wrapper::Membership>, server: &wrapper::Server) -> numeric::WhoReply
+/// Test whether a non-channel WHO mask matches any of the user's nick, username, visible
+/// hostname, server name or realname
+fn user_matches(user: &wrapper::User, pattern: &Pattern) -> bool
+{
+    let server_name = user.server().map(|s| s.name().to_string()).unwrap_or_default();
+
+    [
+        user.nick().to_string(),
+        user.user().to_string(),
+        user.visible_host().to_string(),
+        server_name,
+        user.realname().to_owned(),
+    ].iter().any(|s| pattern.matches(&s.to_ascii_lowercase()))
+}
+
+/// Build the flags field of a WHO reply: H (here) or G (gone), * for opers, then any
+/// channel status prefixes
+fn who_flags(target: &wrapper::User, membership: Option<&wrapper::Membership>) -> String
+{
+    let mut flags = String::from(if target.away_reason().is_some() { "G" } else { "H" });
+    if target.is_oper()
+    {
+        flags.push('*');
+    }
+    if let Some(membership) = membership
+    {
+        flags.push_str(&membership.permissions().to_prefixes());
+    }
+    flags
+}
+
+fn send_who_reply(cmd: &dyn Command, target: &wrapper::User, channel: Option<&wrapper::Channel>,
+                  membership: Option<&wrapper::Membership>, whox: Option<&WhoxQuery>) -> CommandResult
 {
     let chname = channel.map(|c| c.name().value() as &str).unwrap_or("*");
-    let status = format!("H{}", membership.map(|m| m.permissions().to_prefixes()).unwrap_or_else(|| "".to_string()));
-    make_numeric!(WhoReply, chname, target, server, &status, 0)
+    let flags = who_flags(target, membership);
+    let server = target.server()?;
+
+    let Some(whox) = whox else {
+        cmd.numeric(make_numeric!(WhoReply, chname, target, &server, &flags, 0));
+        return Ok(())
+    };
+
+    let mut fields = Vec::new();
+    for field in whox.fields.chars()
+    {
+        fields.push(match field
+        {
+            't' => whox.token.unwrap_or("0").to_owned(),
+            'c' => chname.to_owned(),
+            'u' => target.user().to_string(),
+            // We don't reveal real IP addresses
+            'i' => "255.255.255.255".to_owned(),
+            'h' => target.visible_host().to_string(),
+            's' => server.name().to_string(),
+            'n' => target.nick().to_string(),
+            'f' => flags.clone(),
+            'd' => "0".to_owned(),
+            'l' => "0".to_owned(),
+            'a' => target.account()?.map(|a| a.name().to_string()).unwrap_or_else(|| "0".to_owned()),
+            'o' => "n/a".to_owned(),
+            'r' => format!(":{}", target.realname()),
+            _ => continue
+        });
+    }
+
+    cmd.numeric(make_numeric!(WhoxReply, &fields.join(" ")));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn whox_fields_reordered()
+    {
+        let query = WhoxQuery::parse("%nuc").unwrap();
+
+        assert_eq!(query.fields, "cun");
+        assert_eq!(query.token, None);
+    }
+
+    #[test]
+    fn whox_token()
+    {
+        let query = WhoxQuery::parse("o%tna,123").unwrap();
+
+        assert_eq!(query.fields, "tna");
+        assert_eq!(query.token, Some("123"));
+    }
+
+    #[test]
+    fn whox_without_token()
+    {
+        let query = WhoxQuery::parse("%").unwrap();
+
+        assert_eq!(query.fields, "");
+        assert_eq!(query.token, None);
+    }
+
+    #[test]
+    fn not_whox()
+    {
+        assert!(WhoxQuery::parse("o").is_none());
+    }
 }
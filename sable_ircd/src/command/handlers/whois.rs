@@ -7,7 +7,12 @@ fn whois_handler(cmd: &dyn Command, _source: UserSource, target: wrapper::User)
 
     if let Ok(server) = target.server()
     {
-        cmd.numeric(make_numeric!(WhoisServer, &target, &server));
+        cmd.numeric(make_numeric!(WhoisServer, &target.nick(), server.name().as_ref(), &format!("{:?}", server.id())));
+    }
+
+    if let Some(reason) = target.away_reason()
+    {
+        cmd.numeric(make_numeric!(Away, &target, reason));
     }
 
     if let Ok(Some(account)) = target.account()
@@ -0,0 +1,34 @@
+use super::*;
+use crate::utils::format_timestamp;
+
+#[command_handler("WHOWAS")]
+fn handle_whowas(server: &ClientServer, net: &Network, cmd: &dyn Command,
+                 target: Nickname, count: Option<u32>) -> CommandResult
+{
+    let whowas = server.node().whowas();
+    let now = sable_network::utils::now();
+
+    // A count of zero or less means no limit
+    let limit = count.filter(|c| *c > 0).map(|c| c as usize).unwrap_or(usize::MAX);
+
+    let mut found = false;
+
+    for entry in whowas.entries_for(&target, &net.config().whowas, now).take(limit)
+    {
+        found = true;
+
+        cmd.numeric(make_numeric!(WhowasUser, &entry.nickname, &entry.user.user, &entry.user.visible_host, &entry.user.realname));
+
+        let server_name = entry.server_name.as_ref().map(|s| s.to_string()).unwrap_or_else(|| "*".to_string());
+        cmd.numeric(make_numeric!(WhoisServer, &entry.nickname, &server_name, &format_timestamp(entry.timestamp)));
+    }
+
+    if !found
+    {
+        cmd.numeric(make_numeric!(WasNoSuchNick, &target));
+    }
+
+    cmd.numeric(make_numeric!(EndOfWhowas, &target));
+
+    Ok(())
+}
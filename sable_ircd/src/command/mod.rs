@@ -53,19 +53,29 @@ mod handlers
     mod part;
     mod notice;
     mod privmsg;
+    mod tagmsg;
     mod quit;
+    mod away;
+    mod setname;
+    mod chghost;
     mod mode;
     mod ping;
     mod names;
+    mod list;
+    mod monitor;
     mod who;
     mod whois;
+    mod whowas;
     mod topic;
     mod invite;
+    mod kick;
+    mod rename;
     mod kill;
     mod kline;
     mod oper;
     mod chathistory;
     pub mod register;
+    mod verify;
 
     // Interim solutions that need refinement
     mod session;
@@ -10,6 +10,7 @@ pub trait CommandExt
     fn notice(&self, text: impl ToString);
     fn numeric(&self, numeric: impl Numeric);
     fn new_event(&self, target: impl Into<ObjectId>, detail: impl Into<EventDetails>);
+    fn client_only_tags(&self) -> Vec<state::ClientTag>;
 }
 
 impl<T: Command + ?Sized> CommandExt for T
@@ -29,4 +30,9 @@ impl<T: Command + ?Sized> CommandExt for T
     {
         self.server().node().submit_event(target, detail);
     }
+
+    fn client_only_tags(&self) -> Vec<state::ClientTag>
+    {
+        self.tags().iter().filter(|t| t.name.starts_with('+')).cloned().collect()
+    }
 }
\ No newline at end of file
@@ -22,6 +22,9 @@ pub trait Command : Send + Sync
     /// The arguments supplied to the command
     fn args(&self) -> ArgListIter;
 
+    /// The message tags supplied with the command
+    fn tags(&self) -> &[state::ClientTag];
+
     /// Access the [`ClientServer`]
     fn server(&self) -> &Arc<ClientServer>;
     /// Access the network state applicable to this command handler
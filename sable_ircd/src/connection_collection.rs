@@ -132,13 +132,12 @@ impl ConnectionCollection
         }
     }
 
-/*
-    /// Iterate over connections
-    pub fn iter(&self) -> impl Iterator<Item=&ClientConnection>
+    /// Iterate over all connections
+    pub fn iter(&self) -> impl Iterator<Item=&Arc<ClientConnection>>
     {
         self.client_connections.values()
     }
-*/
+
     /// Get the number of managed connections
     pub fn len(&self) -> usize
     {
@@ -0,0 +1,81 @@
+use super::*;
+use crate::capability::*;
+use crate::capability::message_tag::{MessageTag, prepend_tags};
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static NEXT_BATCH_ID: AtomicU64 = AtomicU64::new(1);
+
+/// A [`MessageSink`] which groups the messages sent through it into an IRCv3 batch.
+///
+/// The opening `BATCH` line is sent on construction; [`end`](Self::end) must be called
+/// to close the batch. Clients which haven't negotiated `batch` see only the inner messages.
+pub struct BatchSink<'a, S: MessageSink + ?Sized>
+{
+    inner: &'a S,
+    id: String,
+}
+
+/// A message tagged as belonging to a batch
+struct BatchedMessage<'a>
+{
+    message: &'a dyn MessageTypeFormat,
+    batch: &'a str,
+}
+
+impl<'a, S: MessageSink + ?Sized> BatchSink<'a, S>
+{
+    /// Open a new batch of the given type on `inner`
+    pub fn start(inner: &'a S, batch_type: &str, args: &[&str]) -> Self
+    {
+        Self::start_with_tags(inner, batch_type, args, Vec::new())
+    }
+
+    /// Open a new batch of the given type on `inner`, attaching `tags` to the opening line
+    pub fn start_with_tags(inner: &'a S, batch_type: &str, args: &[&str], tags: Vec<MessageTag>) -> Self
+    {
+        let id = format!("{:x}", NEXT_BATCH_ID.fetch_add(1, Ordering::Relaxed));
+        let params: Vec<_> = std::iter::once(batch_type).chain(args.iter().copied()).collect();
+
+        inner.send(&message::BatchStart::new(&id, &params.join(" "))
+                        .with_tags(tags)
+                        .with_required_capability(ClientCapability::Batch));
+
+        Self { inner, id }
+    }
+
+    /// Close the batch
+    pub fn end(self)
+    {
+        self.inner.send(&message::BatchEnd::new(&self.id)
+                            .with_required_capability(ClientCapability::Batch));
+    }
+}
+
+impl<S: MessageSink + ?Sized> MessageSink for BatchSink<'_, S>
+{
+    fn send(&self, msg: &dyn MessageTypeFormat)
+    {
+        self.inner.send(&BatchedMessage { message: msg, batch: &self.id });
+    }
+
+    fn user_id(&self) -> Option<UserId>
+    {
+        self.inner.user_id()
+    }
+}
+
+impl MessageTypeFormat for BatchedMessage<'_>
+{
+    fn format_for_client_caps(&self, caps: &ClientCapabilitySet) -> Option<String>
+    {
+        let inner = self.message.format_for_client_caps(caps)?;
+
+        if !caps.has(ClientCapability::Batch)
+        {
+            return Some(inner);
+        }
+
+        Some(prepend_tags(&format!("batch={}", self.batch), &inner))
+    }
+}
@@ -8,9 +8,17 @@ define_messages! {
     Nick    => { (source, newnick: &Nickname)               => ":{source} NICK {newnick}" },
     Join    => { (source, chan: &ChannelName)               => ":{source} JOIN {chan}" },
     Part    => { (source, chan: &ChannelName, msg: &str)    => ":{source} PART {chan} :{msg}" },
+    Kick    => { (source, target, chan: &ChannelName, msg: &str)
+                                                            => ":{source} KICK {chan} {target} :{msg}" },
     Invite  => { (source, target, chan: &ChannelName)       => ":{source} INVITE {target} :{chan}" },
     Quit    => { (source, message: &str)                    => ":{source} QUIT :{message}" },
+    Away    => { (source, reason: &str)                     => ":{source} AWAY :{reason}" },
+    Unaway  => { (source)                                   => ":{source} AWAY" },
     Topic   => { (source, chan: &ChannelName, text: &str)   => ":{source} TOPIC {chan} :{text}" },
+    Rename  => { (source, old: &ChannelName, new: &ChannelName, reason: &str)
+                                                            => ":{source} RENAME {old} {new} :{reason}" },
+    Setname => { (source, realname: &str)                   => ":{source} SETNAME :{realname}" },
+    Chghost => { (source, user: &Username, host: &Hostname) => ":{source} CHGHOST {user} {host}" },
 
     Mode    => { (source, target, changes: &str)            => ":{source} MODE {target} {changes}" },
 
@@ -18,6 +26,7 @@ define_messages! {
     Privmsg => { (source, target, message: &str)            => ":{source} PRIVMSG {target} :{message}" },
     Message => { (source, target, message_type: state::MessageType, message: &str)
                                                             => ":{source} {message_type} {target} :{message}" },
+    Tagmsg  => { (source, target)                           => ":{source} TAGMSG {target}" },
 
     Ping    => { (source, target, cookie: &str)             => ":{source} PING {target} :{cookie}" },
     Pong    => { (source, cookie: &str)                     => ":{source} PONG {source} :{cookie}" },
@@ -32,10 +41,16 @@ define_messages! {
     Note    => { (command: &str, code: &str, context: &str, description: &str)
                                 => "NOTE {command} {code} {context} :{description}" },
 
+    // Batches
+    BatchStart      => { (id: &str, params: &str) => "BATCH +{id} {params}" },
+    BatchEnd        => { (id: &str) => "BATCH -{id}" },
+    Ack             => { (source) => ":{source} ACK" },
+
     // SASL
     Authenticate    => { (data: &str) => "AUTHENTICATE :{data}" },
 
     // Extension messages
     ChatHistoryTarget => { (target_name: &str, timestamp: &str) => "CHATHISTORY TARGETS {target_name} {timestamp}" },
-    Register => { (status: &str, account: Nickname, message: &str) => "REGISTER {status} {account} {message}" }
+    Register => { (status: &str, account: Nickname, message: &str) => "REGISTER {status} {account} :{message}" },
+    Verify   => { (status: &str, account: Nickname, message: &str) => "VERIFY {status} {account} :{message}" }
 }
\ No newline at end of file
@@ -206,4 +206,7 @@ pub mod send_history;
 pub mod send_realtime;
 
 mod message_sink;
-pub use message_sink::*;
\ No newline at end of file
+pub use message_sink::*;
+
+mod batch;
+pub use batch::*;
\ No newline at end of file
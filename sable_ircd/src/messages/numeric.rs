@@ -15,15 +15,24 @@ define_messages! {
     005(ISupport)   => { (data: &str)                           => "{data} :are supported by this server" },
 
     221(UserModeIs)                => { (modestring: &str)         => ":{modestring}" },
+    301(Away)                   => { (nick: &User.nick(), reason: &str)
+                                                                => "{nick} :{reason}" },
+    305(Unaway)                 => { ()                         => ":You are no longer marked as being away" },
+    306(NowAway)                => { ()                         => ":You have been marked as being away" },
     311(WhoisUser)              => { (nick: &User.nick(), user=nick.user(), host=nick.visible_host(), realname=nick.realname())
                                                                 => "{nick} {user} {host} * :{realname}" },
-    312(WhoisServer)            => { (nick: &User.nick(), server: &Server.name(), info=server.id())
-                                                                => "{nick} {server} :{info:?}"},
+    312(WhoisServer)            => { (nick: &Nickname, server: &str, info: &str)
+                                                                => "{nick} {server} :{info}"},
+    314(WhowasUser)             => { (nick: &Nickname, user: &Username, host: &Hostname, realname: &str)
+                                                                => "{nick} {user} {host} * :{realname}" },
     315(EndOfWho)               => { (arg: &str)                => "{arg} :End of /WHO list" },
     318(EndOfWhois)             => { (user: &User.nick())       => "{user} :End of /WHOIS" },
     319(WhoisChannels)          => { (user: &User.nick(), chanlist: &str)
                                                                 => "{user} :{chanlist}" },
 
+    322(ListEntry)              => { (chan: &Channel.name(), visible: usize, topic: &str)
+                                                                => "{chan} {visible} :{topic}" },
+    323(EndOfList)              => { ()                         => ":End of /LIST" },
     324(ChannelModeIs)          => { (chan: &Channel.name(), modes: &ChannelMode.format())
                                                                 => "{chan} {modes}" },
 
@@ -39,14 +48,18 @@ define_messages! {
     352(WhoReply)               => { (chname: &str, user: &User.user(), host=user.visible_host(), server: &Server.name(),
                                       nick=user.nick(), status: &str, hopcount: usize, realname=&user.realname())
                                                 => "{chname} {user} {host} {server} {nick} {status} :{hopcount} {realname}" },
+    354(WhoxReply)              => { (fields: &str)             => "{fields}" },
     353(NamesReply)             => { (is_pub: char, chan: &Channel.name(), content: &str)
                                                                 => "{is_pub} {chan} :{content}" },
+    369(EndOfWhowas)            => { (nick: &Nickname)          => "{nick} :End of WHOWAS" },
     366(EndOfNames)             => { (chan: &Channel.name())    => "{chan} :End of names list" },
 
 
     401(NoSuchTarget)           => { (unknown: &str)            => "{unknown} :No such nick/channel" },
     403(NoSuchChannel)          => { (chname: &ChannelName)     => "{chname} :No such channel" },
     404(CannotSendToChannel)    => { (chan: &ChannelName)       => "{chan} :Cannot send to channel" },
+    406(WasNoSuchNick)          => { (nick: &Nickname)          => "{nick} :There was no such nickname" },
+    417(InputTooLong)           => { ()                         => ":Input line was too long" },
     421(UnknownCommand)         => { (command: &str)            => "{command} :Unknown command" },
     432(ErroneousNickname)      => { (nick: &str)               => "{nick} :Erroneous nickname" },
     433(NicknameInUse)          => { (nick: &Nickname)          => "{nick} :Nickname is already in use." },
@@ -70,6 +83,13 @@ define_messages! {
         => "{chan} {entry} {setter} {ts}"},
     729(EndOfQuietList)   => { (chan: &Channel.name())    => "{chan} :End of channel quiet list" },
 
+    730(MonOnline)          => { (targets: &str)            => ":{targets}" },
+    731(MonOffline)         => { (targets: &str)            => ":{targets}" },
+    732(MonList)            => { (targets: &str)            => ":{targets}" },
+    733(EndOfMonList)       => { ()                         => ":End of MONITOR list" },
+    734(MonListFull)        => { (limit: usize, targets: &str)
+                                                            => "{limit} {targets} :Monitor list is full" },
+
     346(InviteList)        => { (chan: &Channel.name(), entry: &ListModeEntry.pattern(), setter=entry.setter(), ts=entry.timestamp())
         => "{chan} {entry} {setter} {ts}"},
     347(EndOfInviteList)   => { (chan: &Channel.name())    => "{chan} :End of channel invite list" },
@@ -86,6 +106,7 @@ define_messages! {
 
     481(NotOper)            => { ()     => ":You're not an IRC operator" },
     491(NoOperConf)         => { ()     => ":No oper configuration found" },
+    723(NoPrivs)            => { (privilege: &str)  => "{privilege} :Insufficient oper privileges." },
 
     440(ServicesNotAvailable) => { () => ":Services are not available"},
 
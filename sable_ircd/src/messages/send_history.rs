@@ -1,9 +1,12 @@
 use sable_network::prelude::*;
 use sable_network::utils::*;
 use crate::messages::MessageSink;
+use crate::messages::MessageTypeFormat;
 use crate::capability::CapableMessage;
 use crate::capability::ClientCapability;
 use crate::capability::WithSupportedTags;
+use crate::capability::TaggableMessage;
+use crate::capability::message_tag::MessageTag;
 use crate::errors::HandleResult;
 
 use super::message;
@@ -23,6 +26,8 @@ impl SendHistoryItem for HistoryLogEntry
             NetworkStateChange::NewUser(detail) => detail.send_to(conn, self),
             NetworkStateChange::UserNickChange(detail) => detail.send_to(conn, self),
             NetworkStateChange::UserModeChange(detail) => detail.send_to(conn, self),
+            NetworkStateChange::UserAwayChange(detail) => detail.send_to(conn, self),
+            NetworkStateChange::UserDetailsChange(detail) => detail.send_to(conn, self),
             NetworkStateChange::UserQuit(detail) => detail.send_to(conn, self),
             NetworkStateChange::BulkUserQuit(detail) => detail.send_to(conn, self),
             NetworkStateChange::ChannelModeChange(detail) => detail.send_to(conn, self),
@@ -32,10 +37,12 @@ impl SendHistoryItem for HistoryLogEntry
             NetworkStateChange::MembershipFlagChange(detail) => detail.send_to(conn, self),
             NetworkStateChange::ChannelJoin(detail) => detail.send_to(conn, self),
             NetworkStateChange::ChannelPart(detail) => detail.send_to(conn, self),
+            NetworkStateChange::ChannelKick(detail) => detail.send_to(conn, self),
             NetworkStateChange::ChannelInvite(detail) => detail.send_to(conn, self),
             NetworkStateChange::ChannelRename(detail) => detail.send_to(conn, self),
             NetworkStateChange::NewMessage(detail) => detail.send_to(conn, self),
             NetworkStateChange::NewServer(detail) => detail.send_to(conn, self),
+            NetworkStateChange::NewChannel(detail) => detail.send_to(conn, self),
             NetworkStateChange::ServerQuit(detail) => detail.send_to(conn, self),
             NetworkStateChange::NewAuditLogEntry(detail) => detail.send_to(conn, self),
             NetworkStateChange::UserLoginChange(detail) => detail.send_to(conn, self),
@@ -80,6 +87,58 @@ impl SendHistoryItem for update::UserModeChange
     }
 }
 
+impl SendHistoryItem for update::UserAwayChange
+{
+    fn send_to(&self, conn: &(impl MessageSink + ?Sized), from_entry: &HistoryLogEntry) -> HandleResult
+    {
+        match &self.new_reason
+        {
+            Some(reason) =>
+            {
+                let message = message::Away::new(&self.user, reason)
+                                            .with_tags_from(from_entry)
+                                            .with_required_capability(ClientCapability::AwayNotify);
+                conn.send(&message);
+            }
+            None =>
+            {
+                let message = message::Unaway::new(&self.user)
+                                            .with_tags_from(from_entry)
+                                            .with_required_capability(ClientCapability::AwayNotify);
+                conn.send(&message);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl SendHistoryItem for update::UserDetailsChange
+{
+    fn send_to(&self, conn: &(impl MessageSink + ?Sized), from_entry: &HistoryLogEntry) -> HandleResult
+    {
+        if self.old_username != self.user.user.user || self.old_visible_host != self.user.user.visible_host
+        {
+            // CHGHOST is sourced from the user's old mask, so that clients can match it up
+            let source_str = format!("{}!{}@{}", self.user.nickname, self.old_username, self.old_visible_host);
+            let message = message::Chghost::new(&source_str, &self.user.user.user, &self.user.user.visible_host)
+                                        .with_tags_from(from_entry)
+                                        .with_required_capability(ClientCapability::Chghost);
+            conn.send(&message);
+        }
+
+        if self.old_realname != self.user.user.realname
+        {
+            let message = message::Setname::new(&self.user, &self.user.user.realname)
+                                        .with_tags_from(from_entry)
+                                        .with_required_capability(ClientCapability::Setname);
+            conn.send(&message);
+        }
+
+        Ok(())
+    }
+}
+
 impl SendHistoryItem for update::UserQuit
 {
     fn send_to(&self, conn: &(impl MessageSink + ?Sized), from_entry: &HistoryLogEntry) -> HandleResult
@@ -213,6 +272,19 @@ impl SendHistoryItem for update::ChannelPart
     }
 }
 
+impl SendHistoryItem for update::ChannelKick
+{
+    fn send_to(&self, conn: &(impl MessageSink + ?Sized), from_entry: &HistoryLogEntry) -> HandleResult
+    {
+        let message = message::Kick::new(&self.source, &self.user, &self.channel.name, &self.message)
+                                    .with_tags_from(from_entry);
+
+        conn.send(&message);
+
+        Ok(())
+    }
+}
+
 impl SendHistoryItem for update::ChannelInvite
 {
     fn send_to(&self, conn: &(impl MessageSink + ?Sized), from_entry: &HistoryLogEntry) -> HandleResult
@@ -229,9 +301,17 @@ impl SendHistoryItem for update::ChannelInvite
 
 impl SendHistoryItem for update::ChannelRename
 {
-    fn send_to(&self, _conn: &(impl MessageSink + ?Sized), _from_entry: &HistoryLogEntry) -> HandleResult
+    fn send_to(&self, conn: &(impl MessageSink + ?Sized), from_entry: &HistoryLogEntry) -> HandleResult
     {
-        todo!();
+        // Clients without the capability get a PART and JOIN instead, but that needs the
+        // current state of the channel, so is only done in realtime
+        let message = message::Rename::new(&self.source, &self.old_name, &self.new_name, &self.message)
+                                    .with_tags_from(from_entry)
+                                    .with_required_capability(ClientCapability::ChannelRename);
+
+        conn.send(&message);
+
+        Ok(())
     }
 }
 
@@ -239,26 +319,52 @@ impl SendHistoryItem for update::NewMessage
 {
     fn send_to(&self, conn: &(impl MessageSink + ?Sized), from_entry: &HistoryLogEntry) -> HandleResult
     {
-        let message = message::Message::new(&self.source, &self.target, self.message.message_type, &self.message.text)
-                                    .with_tags_from(from_entry);
+        let client_tags = self.message.tags.iter().map(MessageTag::from).collect();
+
+        if self.message.message_type == state::MessageType::Tagmsg
+        {
+            let message = message::Tagmsg::new(&self.source, &self.target)
+                                        .with_tags_from(from_entry)
+                                        .with_tags(client_tags)
+                                        .with_required_capability(ClientCapability::MessageTags);
+            send_echoable_message(conn, &self.source, message);
+        }
+        else
+        {
+            let message = message::Message::new(&self.source, &self.target, self.message.message_type, &self.message.text)
+                                        .with_tags_from(from_entry)
+                                        .with_tags(client_tags);
+            send_echoable_message(conn, &self.source, message);
+        }
+
+        Ok(())
+    }
+}
 
-        // Users should only see their own messages echoed if they've asked for it
-        match &self.source
+fn send_echoable_message(conn: &(impl MessageSink + ?Sized), source: &update::HistoricMessageSource, message: impl MessageTypeFormat)
+{
+    // Users should only see their own messages echoed if they've asked for it
+    match source
+    {
+        update::HistoricMessageSource::User(user) =>
         {
-            update::HistoricMessageSource::User(user) =>
+            if conn.user_id() == Some(user.user.id)
+            {
+                conn.send(&message.with_required_capability(ClientCapability::EchoMessage));
+            }
+            else
             {
-                if conn.user_id() == Some(user.user.id)
-                {
-                    conn.send(&message.with_required_capability(ClientCapability::EchoMessage));
-                }
-                else
-                {
-                    conn.send(&message);
-                }
+                conn.send(&message);
             }
-            _ => conn.send(&message)
         }
+        _ => conn.send(&message)
+    }
+}
 
+impl SendHistoryItem for update::NewChannel
+{
+    fn send_to(&self, _conn: &(impl MessageSink + ?Sized), _from_entry: &HistoryLogEntry) -> HandleResult
+    {
         Ok(())
     }
 }
@@ -5,6 +5,7 @@ use crate::errors::HandleResult;
 
 use super::*;
 use super::send_history::SendHistoryItem;
+use crate::capability::{ClientCapability, WithoutCapabilitySink};
 
 /// Extension trait for network updates that behave differently in realtime than in history playback
 pub(crate) trait SendRealtimeItem : SendHistoryItem
@@ -20,6 +21,7 @@ impl SendRealtimeItem for HistoryLogEntry
         match &self.details
         {
             NetworkStateChange::ChannelJoin(detail) => detail.send_now(conn, self, server),
+            NetworkStateChange::ChannelRename(detail) => detail.send_now(conn, self, server),
             _ => self.send_to(conn, self)
         }
     }
@@ -55,6 +57,47 @@ impl SendRealtimeItem for update::ChannelJoin
 
         crate::utils::send_channel_names(server, conn, &user, &channel)?;
 
+        if let Some(entry_message) = channel.is_registered().as_ref().and_then(|r| r.entry_message())
+        {
+            conn.send(&message::Notice::new(server, &user, &format!("[{}] {}", channel.name(), entry_message)));
+        }
+
+        Ok(())
+    }
+}
+
+impl SendRealtimeItem for update::ChannelRename
+{
+    fn send_now(&self, conn: &impl MessageSink, from_entry: &HistoryLogEntry, server: &ClientServer) -> HandleResult
+    {
+        self.send_to(conn, from_entry)?;
+
+        // Clients which don't understand RENAME see themselves leave the old channel and join the new one
+        let Some(user_id) = conn.user_id() else { return Ok(()) };
+
+        let network = server.network();
+        let channel = network.channel(self.channel.id)?;
+        let user = network.user(user_id)?;
+        let fallback = WithoutCapabilitySink::new(conn, ClientCapability::ChannelRename);
+
+        let part_message = if self.message.is_empty() {
+            format!("Channel renamed to {}", self.new_name)
+        } else {
+            format!("Channel renamed to {}: {}", self.new_name, self.message)
+        };
+        fallback.send(&message::Part::new(&user, &self.old_name, &part_message));
+        fallback.send(&message::Join::new(&user, &self.new_name));
+
+        if let Some(topic) = channel.topic()
+        {
+            fallback.send(&numeric::TopicIs::new(&channel, topic.text())
+                              .format_for(server, &user));
+            fallback.send(&numeric::TopicSetBy::new(&channel, topic.setter(), topic.timestamp())
+                              .format_for(server, &user));
+        }
+
+        crate::utils::send_channel_names(server, &fallback, &user, &channel)?;
+
         Ok(())
     }
 }
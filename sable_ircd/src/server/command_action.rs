@@ -75,6 +75,13 @@ impl ClientServer
 
                     connections.add_user(user_id, connection_id);
                 }
+                drop(connections);
+
+                // Undo the away status that was set when the last connection detached
+                if self.network().user(user_id).map(|u| u.is_auto_away()).unwrap_or(false)
+                {
+                    self.node.submit_event(user_id, event::details::UserAwayChange { reason: None, automatic: false });
+                }
             }
 
             CommandAction::UpdateConnectionCaps(conn_id, new_caps) =>
@@ -129,6 +129,9 @@ impl ClientServer
         ret.add(ISupportEntry::simple("EXCEPTS"));
         ret.add(ISupportEntry::simple("INVEX"));
         ret.add(ISupportEntry::simple("FNC"));
+        ret.add(ISupportEntry::string("ELIST", "CMNTU"));
+        ret.add(ISupportEntry::int("MONITOR", crate::client::MONITOR_LIMIT as i32));
+        ret.add(ISupportEntry::simple("WHOX"));
 
         ret.add(ISupportEntry::string("CASEMAPPING", "ascii"));
 
@@ -173,10 +176,12 @@ impl ClientServer
                     if let Some(userid) = conn.user_id() {
                         // If the user has a session key set, then they're in persistent session mode
                         // and shouldn't be quit just because one of their connections closed
-                        let should_quit = if let Ok(user) = self.network().user(userid) {
-                            user.session_key().is_none()
+                        let (should_quit, should_set_away) = if let Ok(user) = self.network().user(userid) {
+                            // If this was the last attached connection, mark the user as away
+                            let last_connection = self.connections.read().get_user(userid).count() <= 1;
+                            (user.session_key().is_none(), last_connection && user.away_reason().is_none())
                         } else {
-                            true
+                            (true, false)
                         };
 
                         if should_quit
@@ -188,6 +193,16 @@ impl ClientServer
                                 }
                             )).await;
                         }
+                        else if should_set_away
+                        {
+                            self.apply_action(CommandAction::state_change(
+                                userid,
+                                details::UserAwayChange {
+                                    reason: Some("Detached".to_string()),
+                                    automatic: true,
+                                }
+                            )).await;
+                        }
                     }
                 }
                 self.connections.write().remove(msg.source);
@@ -200,22 +215,37 @@ impl ClientServer
         let connections = self.connections.read();
         for (conn_id, message) in connections.poll_messages().collect::<Vec<_>>()
         {
-            if let Some(parsed) = ClientMessage::parse(conn_id, &message)
+            match ClientMessage::parse(conn_id, &message)
             {
-                if let Ok(connection) = connections.get(conn_id)
+                Ok(parsed) =>
                 {
-                    if let Ok(command) = ClientCommand::new(Arc::clone(&self), connection, parsed)
+                    if let Ok(connection) = connections.get(conn_id)
                     {
-                        if let Some(async_handler) = self.command_dispatcher.dispatch_command(command)
+                        if let Ok(command) = ClientCommand::new(Arc::clone(&self), connection, parsed)
                         {
-                            async_handlers.add(async_handler);
+                            if let Some(async_handler) = self.command_dispatcher.dispatch_command(command)
+                            {
+                                async_handlers.add(async_handler);
+                            }
                         }
                     }
                 }
-            }
-            else
-            {
-                tracing::info!(?message, "Failed parsing")
+                Err(ClientMessageError::TagsTooLong) =>
+                {
+                    if let Ok(connection) = connections.get(conn_id)
+                    {
+                        let network = self.network();
+                        match connection.user_id().and_then(|id| network.user(id).ok())
+                        {
+                            Some(user) => connection.send(&numeric::InputTooLong::new_for(self.as_ref(), &user)),
+                            None => connection.send(&numeric::InputTooLong::new_for(self.as_ref(), &UnknownTarget)),
+                        }
+                    }
+                }
+                Err(ClientMessageError::Empty) =>
+                {
+                    tracing::info!(?message, "Failed parsing")
+                }
             }
         }
         drop(connections);
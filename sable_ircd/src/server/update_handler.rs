@@ -23,6 +23,30 @@ impl ClientServer
                             let new_user = detail.clone();
                             drop(history);
                             self.handle_new_user(&new_user)?;
+                            self.notify_monitors(&new_user.user.nickname, Some(&new_user.user))?;
+                        }
+                        NetworkStateChange::UserNickChange(detail) =>
+                        {
+                            let old_nick = detail.old_nick;
+                            let user = update::HistoricUser { user: detail.user.clone(), nickname: detail.new_nick };
+                            drop(history);
+                            self.notify_monitors(&old_nick, None)?;
+                            self.notify_monitors(&user.nickname, Some(&user))?;
+                        }
+                        NetworkStateChange::UserQuit(detail) =>
+                        {
+                            let nick = detail.nickname;
+                            drop(history);
+                            self.notify_monitors(&nick, None)?;
+                        }
+                        NetworkStateChange::BulkUserQuit(detail) =>
+                        {
+                            let nicks: Vec<_> = detail.items.iter().map(|i| i.nickname).collect();
+                            drop(history);
+                            for nick in nicks
+                            {
+                                self.notify_monitors(&nick, None)?;
+                            }
                         }
                         NetworkStateChange::ServicesUpdate(detail) =>
                         {
@@ -30,6 +54,12 @@ impl ClientServer
                             drop(history);
                             self.handle_services_update(&update)?;
                         }
+                        NetworkStateChange::UserLoginChange(detail) =>
+                        {
+                            let user_id = detail.user.user.id;
+                            drop(history);
+                            self.handle_user_login(user_id)?;
+                        }
                         _ =>
                         {
                         }
@@ -67,6 +97,66 @@ impl ClientServer
         Ok(())
     }
 
+    fn handle_user_login(&self, user_id: UserId) -> HandleResult
+    {
+        let net = self.node.network();
+        let user = net.user(user_id)?;
+
+        // Only the user's own server grants automatic permissions, to avoid duplicate events
+        if user.server()?.id() != self.node.id()
+        {
+            return Ok(());
+        }
+
+        for membership in user.channels()
+        {
+            let channel = membership.channel()?;
+            let current = membership.permissions();
+            let auto = self.policy().auto_permissions(&user, &channel);
+
+            let added = auto & !current;
+            if ! added.is_empty()
+            {
+                self.node.submit_event(membership.id(), event::MembershipFlagChange {
+                    changed_by: self.node.id().into(),
+                    added,
+                    removed: MembershipFlagSet::new(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Notify connections monitoring `nick` that it is now online (as `user`) or offline
+    fn notify_monitors(&self, nick: &Nickname, user: Option<&update::HistoricUser>) -> HandleResult
+    {
+        let net = self.node.network();
+
+        for conn in self.connections.read().iter()
+        {
+            if !conn.is_monitoring(nick)
+            {
+                continue;
+            }
+            let target = match conn.user_id().and_then(|id| net.user(id).ok())
+            {
+                Some(target) => target,
+                None => continue
+            };
+
+            match user
+            {
+                Some(user) => conn.send(&numeric::MonOnline::new(&<update::HistoricUser as MessageSource>::format(user))
+                                            .format_for(self, &target)),
+                None => conn.send(&numeric::MonOffline::new(nick.as_ref())
+                                            .format_for(self, &target)),
+            }
+        }
+
+        Ok(())
+    }
+
     fn handle_new_user(&self, detail: &update::NewUser) -> HandleResult
     {
         let net = self.node.network();
@@ -97,7 +187,10 @@ impl ClientServer
             Some(state) =>
             {
                 let mut mechanisms = state.sasl_mechanisms.clone();
-                mechanisms.push("EXTERNAL".to_string());
+                if ! mechanisms.iter().any(|m| m == "EXTERNAL")
+                {
+                    mechanisms.push("EXTERNAL".to_string());
+                }
                 self.client_caps.enable_with_values(ClientCapability::Sasl, &mechanisms);
             }
             None =>
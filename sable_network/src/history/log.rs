@@ -175,6 +175,13 @@ impl NetworkHistoryLog
         }
     }
 
+    /// Find the entry in a user's history log which records the given message
+    pub fn entry_for_message(&self, user: UserId, message: MessageId) -> Option<&HistoryLogEntry>
+    {
+        self.entries_for_user_reverse(user)
+            .find(|entry| matches!(&entry.details, NetworkStateChange::NewMessage(detail) if detail.message.id == message))
+    }
+
     pub fn add(&self, details: NetworkStateChange, timestamp: i64) -> &HistoryLogEntry
     {
         let index = self.entries.push_with_index(HistoryLogEntry {
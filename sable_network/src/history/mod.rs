@@ -1,2 +1,5 @@
 mod log;
 pub use log::*;
+
+mod whowas;
+pub use whowas::*;
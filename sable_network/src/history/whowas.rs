@@ -0,0 +1,142 @@
+use crate::prelude::*;
+use crate::network::config::WhowasConfig;
+
+use std::collections::{
+    HashMap,
+    VecDeque,
+};
+use serde::{
+    Serialize,
+    Deserialize,
+};
+
+/// A snapshot of a user, taken when they stopped using a nickname
+#[derive(Debug,Clone,Serialize,Deserialize)]
+pub struct WhowasEntry
+{
+    pub nickname: Nickname,
+    pub user: state::User,
+    pub server_name: Option<ServerName>,
+    pub timestamp: i64,
+}
+
+/// A bounded per-nickname index of [`WhowasEntry`] records, used to answer WHOWAS queries
+pub struct WhowasIndex
+{
+    entries: HashMap<Nickname, VecDeque<WhowasEntry>>,
+}
+
+/// Saved state of a [`WhowasIndex`]
+#[derive(Debug,Default,Serialize,Deserialize)]
+pub struct WhowasIndexState
+{
+    entries: Vec<(Nickname, VecDeque<WhowasEntry>)>,
+}
+
+impl WhowasIndex
+{
+    pub fn new() -> Self
+    {
+        Self {
+            entries: HashMap::new()
+        }
+    }
+
+    /// Record a new entry, discarding any for the same nickname which exceed the configured
+    /// retention count or age
+    pub fn add(&mut self, entry: WhowasEntry, config: &WhowasConfig)
+    {
+        let min_timestamp = entry.timestamp - config.max_age;
+
+        let list = self.entries.entry(entry.nickname).or_default();
+        list.push_front(entry);
+        list.truncate(config.max_entries);
+        list.retain(|e| e.timestamp >= min_timestamp);
+    }
+
+    /// Iterate over the retained entries for a given nickname, most recent first
+    pub fn entries_for(&self, nickname: &Nickname, config: &WhowasConfig, now: i64) -> impl Iterator<Item=&WhowasEntry>
+    {
+        let min_timestamp = now - config.max_age;
+
+        self.entries.get(nickname)
+                    .into_iter()
+                    .flatten()
+                    .filter(move |e| e.timestamp >= min_timestamp)
+    }
+
+    /// Remove all entries older than the configured maximum age
+    pub fn expire(&mut self, config: &WhowasConfig, now: i64)
+    {
+        let min_timestamp = now - config.max_age;
+
+        for list in self.entries.values_mut()
+        {
+            list.retain(|e| e.timestamp >= min_timestamp);
+        }
+        self.entries.retain(|_, list| !list.is_empty());
+    }
+}
+
+impl Default for WhowasIndex
+{
+    fn default() -> Self
+    {
+        Self::new()
+    }
+}
+
+impl crate::saveable::Saveable for WhowasIndex
+{
+    type Saved = WhowasIndexState;
+
+    fn save(self) -> Self::Saved
+    {
+        WhowasIndexState {
+            entries: self.entries.into_iter().collect()
+        }
+    }
+
+    fn restore(from: Self::Saved) -> Self
+    {
+        Self {
+            entries: from.entries.into_iter().collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use std::str::FromStr;
+
+    fn entry(nick: &str, timestamp: i64) -> WhowasEntry
+    {
+        let user = state::User::new(UserId::new(ServerId::new(1), EpochId::new(1), 1), ServerId::new(1),
+                                    Username::from_str("user").unwrap(), Hostname::from_str("host.name").unwrap(),
+                                    "realname".to_string(), state::UserMode::new(UserModeSet::new()), None);
+        WhowasEntry { nickname: Nickname::from_str(nick).unwrap(), user, server_name: None, timestamp }
+    }
+
+    #[test]
+    fn retention_limits()
+    {
+        let config = WhowasConfig { max_entries: 2, max_age: 100 };
+        let nick = Nickname::from_str("aaa").unwrap();
+        let mut index = WhowasIndex::new();
+
+        index.add(entry("aaa", 10), &config);
+        index.add(entry("aaa", 20), &config);
+        index.add(entry("aaa", 30), &config);
+
+        let timestamps: Vec<_> = index.entries_for(&nick, &config, 50).map(|e| e.timestamp).collect();
+        assert_eq!(timestamps, vec![30, 20]);
+
+        let timestamps: Vec<_> = index.entries_for(&nick, &config, 125).map(|e| e.timestamp).collect();
+        assert_eq!(timestamps, vec![30]);
+
+        index.expire(&config, 200);
+        assert_eq!(index.entries_for(&nick, &config, 0).count(), 0);
+    }
+}
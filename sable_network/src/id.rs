@@ -65,4 +65,16 @@ impl MembershipId {
 impl ChannelAccessId {
     pub fn account(&self) -> AccountId { self.0 }
     pub fn channel(&self) -> ChannelRegistrationId { self.1 }
-}
\ No newline at end of file
+}
+
+impl MessageId {
+    /// Format this ID as a client-visible `msgid` tag value
+    pub fn to_msgid(&self) -> String { format!("{}-{}-{}", (self.0).0, (self.1).0, self.2) }
+
+    /// Parse a `msgid` tag value previously generated by [`to_msgid`](Self::to_msgid)
+    pub fn from_msgid(s: &str) -> Option<Self>
+    {
+        let mut parts = s.splitn(3, '-').map(|p| p.parse::<LocalId>().ok());
+        Some(Self::new(ServerId::new(parts.next()??), EpochId::new(parts.next()??), parts.next()??))
+    }
+}
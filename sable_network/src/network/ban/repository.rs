@@ -119,6 +119,29 @@ impl BanRepository
         self.all_bans.get(id)
     }
 
+    /// Iterate over all bans in the repository
+    pub fn iter(&self) -> impl Iterator<Item=&state::NetworkBan>
+    {
+        self.all_bans.values()
+    }
+
+    /// Look up the ban, if any, which has exactly the given match criteria
+    pub fn find_by_matcher(&self, matcher: &NetworkBanMatch) -> Option<&state::NetworkBan>
+    {
+        let search_vec = match &matcher.host
+        {
+            NetworkBanHostMatch::ExactIp(ip) => self.exact_ip_bans.get(ip),
+            NetworkBanHostMatch::IpRange(ip_net) => self.ip_net_bans.get(&ip_net.network()),
+            NetworkBanHostMatch::ExactHostname(host) => self.exact_host_bans.get(host),
+            NetworkBanHostMatch::HostnameRange(host) => self.host_range_bans.get(host),
+            NetworkBanHostMatch::HostnameMask(_) => Some(&self.freeform_hostmask_bans),
+        };
+
+        search_vec?.iter()
+                   .filter_map(|id| self.all_bans.get(id))
+                   .find(|ban| &ban.matcher == matcher)
+    }
+
     pub fn find(&self, user_details: &UserDetails) -> Option<&state::NetworkBan>
     {
         let mut candidates = Vec::new();
@@ -82,4 +82,47 @@ fn parse_hostname_freeform()
     let host = Pattern::new("foo.*.example.com".to_owned());
     let expected = NetworkBanHostMatch::HostnameMask(host);
     assert_eq!(match1, expected);
-}
\ No newline at end of file
+}
+
+fn ban_for(ids: &ObjectIdGenerator, mask: &str) -> crate::network::state::NetworkBan
+{
+    let (user, host) = mask.split_once('@').unwrap();
+
+    crate::network::state::NetworkBan {
+        id: ids.next_network_ban(),
+        created_by: ids.next_event(),
+        matcher: NetworkBanMatch::from_user_host(user, host).unwrap(),
+        action: NetworkBanAction::RefuseConnection(true),
+        timestamp: 0,
+        expires: 0,
+        reason: "reason".to_owned(),
+        oper_reason: None,
+        setter_info: "setter".to_owned(),
+    }
+}
+
+#[test]
+fn find_ban_by_matcher()
+{
+    let ids = ObjectIdGenerator::new(ServerId::new(1), EpochId::new(1));
+    let mut repo = BanRepository::new();
+
+    for mask in ["*@192.168.0.1", "*@*.example.com", "foo@*.example.com", "*@foo.*.example.com"]
+    {
+        repo.add(ban_for(&ids, mask)).unwrap();
+    }
+
+    let matcher = NetworkBanMatch::from_user_host("foo", "*.example.com").unwrap();
+    let found = repo.find_by_matcher(&matcher).unwrap();
+    assert_eq!(found.matcher, matcher);
+
+    let matcher = NetworkBanMatch::from_user_host("*", "foo.*.example.com").unwrap();
+    assert!(repo.find_by_matcher(&matcher).is_some());
+
+    let matcher = NetworkBanMatch::from_user_host("bar", "*.example.com").unwrap();
+    assert!(repo.find_by_matcher(&matcher).is_none());
+
+    let found_id = repo.find_by_matcher(&NetworkBanMatch::from_user_host("*", "192.168.0.1").unwrap()).unwrap().id;
+    repo.remove(found_id);
+    assert!(repo.find_by_matcher(&NetworkBanMatch::from_user_host("*", "192.168.0.1").unwrap()).is_none());
+}
@@ -9,12 +9,38 @@ use crate::validated::*;
 pub struct NetworkConfig
 {
     pub opers: Vec<OperConfig>,
+    #[serde(default)]
+    pub oper_classes: Vec<OperClassConfig>,
     pub debug_mode: bool,
 
     #[serde_as(as = "HashMap<_, state::HumanReadableChannelAccessSet>")]
     pub default_roles: HashMap<state::ChannelRoleName, state::ChannelAccessSet>,
 
     pub alias_users: Vec<AliasUser>,
+
+    #[serde(default)]
+    pub whowas: WhowasConfig,
+}
+
+/// Retention limits for the WHOWAS index
+#[derive(Debug,Clone,Serialize,Deserialize)]
+pub struct WhowasConfig
+{
+    /// Maximum number of entries kept for each nickname
+    pub max_entries: usize,
+    /// Maximum age, in seconds, of entries to be kept
+    pub max_age: i64,
+}
+
+impl Default for WhowasConfig
+{
+    fn default() -> Self
+    {
+        Self {
+            max_entries: 10,
+            max_age: 7 * 24 * 60 * 60,
+        }
+    }
 }
 
 #[derive(Debug,Clone,Serialize,Deserialize)]
@@ -33,17 +59,43 @@ pub struct OperConfig
 {
     pub name: String,
     pub hash: String,
+    /// The name of the [`OperClassConfig`] which determines this oper's privileges. An oper
+    /// block without a class can't be used.
+    #[serde(default)]
+    pub class: Option<String>,
+    /// If set, the oper must be connected with a TLS client certificate with this fingerprint
+    #[serde(default)]
+    pub fingerprint: Option<String>,
+    /// If set, the oper must be logged in to this services account
+    #[serde(default)]
+    pub account: Option<Nickname>,
+}
+
+/// A named set of privileges which can be granted to opers
+#[derive(Debug,Clone,Serialize,Deserialize)]
+pub struct OperClassConfig
+{
+    pub name: String,
+    pub privileges: state::OperPrivilegeSet,
 }
 
 impl NetworkConfig
 {
+    /// Look up an oper class by name
+    pub fn oper_class(&self, name: &str) -> Option<&OperClassConfig>
+    {
+        self.oper_classes.iter().find(|c| c.name == name)
+    }
+
     pub fn new() -> Self
     {
         Self {
             opers: Vec::new(),
+            oper_classes: Vec::new(),
             debug_mode: false,
             default_roles: HashMap::new(),
             alias_users: Vec::new(),
+            whowas: WhowasConfig::default(),
         }
     }
 }
\ No newline at end of file
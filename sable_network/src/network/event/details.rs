@@ -13,6 +13,13 @@ EventDetails => {
         pub user: UserId,
     }
 
+    /// Server-initiated removal of a user from a nickname, for example to enforce a
+    /// nickname registration. The user is moved to their ID-derived fallback nickname.
+    #[target_type(NicknameId)]
+    struct ForceNickChange {
+        pub user: UserId,
+    }
+
     #[target_type(UserId)]
     struct NewUser {
         pub nickname: Nickname,
@@ -36,9 +43,28 @@ EventDetails => {
         pub removed: UserModeSet,
     }
 
+    #[target_type(UserId)]
+    struct UserAwayChange {
+        pub reason: Option<String>,
+        /// Set when the server marks a user away because their last connection detached,
+        /// so that the status can be cleared again when a connection reattaches
+        #[serde(default)]
+        pub automatic: bool,
+    }
+
+    #[target_type(UserId)]
+    struct UserDetailsChange {
+        pub username: Option<Username>,
+        pub visible_host: Option<Hostname>,
+        pub realname: Option<String>,
+    }
+
     #[target_type(UserId)]
     struct OperUp {
-        pub oper_name: String
+        pub oper_name: String,
+        /// Absent in events from servers which predate oper classes, granting nothing
+        #[serde(default)]
+        pub privileges: state::OperPrivilegeSet,
     }
 
     #[target_type(ChannelId)]
@@ -47,6 +73,13 @@ EventDetails => {
         pub mode: state::ChannelMode,
     }
 
+    #[target_type(ChannelId)]
+    struct ChannelRename {
+        pub source: ObjectId,
+        pub new_name: ChannelName,
+        pub message: String,
+    }
+
     #[target_type(ChannelId)]
     struct ChannelModeChange {
         pub changed_by: ObjectId,
@@ -59,7 +92,7 @@ EventDetails => {
     struct NewListModeEntry {
         pub list: ListModeId,
         pub pattern: Pattern,
-        pub setter: UserId,
+        pub setter: ObjectId,
     }
 
     #[target_type(ListModeEntryId)]
@@ -93,6 +126,12 @@ EventDetails => {
         pub message: String,
     }
 
+    #[target_type(MembershipId)]
+    struct ChannelKick {
+        pub source: ObjectId,
+        pub message: String,
+    }
+
     #[target_type(InviteId)]
     struct ChannelInvite {
         pub source: UserId,
@@ -104,6 +143,8 @@ EventDetails => {
         pub target: ObjectId, // Can be user or channel
         pub message_type: state::MessageType,
         pub text: String,
+        #[serde(default)]
+        pub tags: Vec<state::ClientTag>,
     }
 
     #[target_type(NetworkBanId)]
@@ -121,7 +162,7 @@ EventDetails => {
 
     #[target_type(NetworkBanId)]
     struct RemoveNetworkBan {
-        pub remover: UserId,
+        pub remover: ObjectId,
     }
 
     #[target_type(ServerId)]
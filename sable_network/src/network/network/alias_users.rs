@@ -19,6 +19,8 @@ impl Network
                 oper_privileges: None,
                 account: None,
                 session_key: None,
+                away_reason: None,
+                auto_away: false,
             });
         }
 
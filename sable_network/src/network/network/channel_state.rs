@@ -21,7 +21,8 @@ impl Network {
     }
 
     /// Rename a channel
-    fn do_rename_channel(&mut self, channel_id: ChannelId, new_name: ChannelName, event: &Event, updates: &dyn NetworkUpdateReceiver)
+    fn do_rename_channel(&mut self, channel_id: ChannelId, new_name: ChannelName, source: HistoricMessageSource, message: String,
+                         event: &Event, updates: &dyn NetworkUpdateReceiver)
     {
         if let Some(channel) = self.channels.get_mut(&channel_id)
         {
@@ -30,12 +31,23 @@ impl Network {
 
             updates.notify(update::ChannelRename {
                 channel: channel.clone(),
+                source,
                 old_name,
                 new_name,
+                message,
             }, event);
         }
     }
 
+    /// Rename the loser of a channel name collision to its hashed name
+    fn rename_colliding_channel(&mut self, channel_id: ChannelId, event: &Event, updates: &dyn NetworkUpdateReceiver)
+    {
+        let newname = state_utils::hashed_channel_name_for(channel_id);
+        let source = self.translate_state_change_source(ObjectId::Server(event.id.server()));
+
+        self.do_rename_channel(channel_id, newname, source, "Channel name collision".to_string(), event, updates);
+    }
+
     pub(super) fn new_channel(&mut self, target: ChannelId, event: &Event, details: &details::NewChannel, updates: &dyn NetworkUpdateReceiver)
     {
         // Take a local copy in case we need to change the name due to a collision
@@ -49,9 +61,7 @@ impl Network {
             if self.should_replace_channel(existing_id, target)
             {
                 // The new one wins. Rename the existing channel
-                let newname = state_utils::hashed_channel_name_for(existing_id);
-
-                self.do_rename_channel(existing_id, newname, event, updates);
+                self.rename_colliding_channel(existing_id, event, updates);
             }
             else
             {
@@ -59,8 +69,38 @@ impl Network {
                 details.name = state_utils::hashed_channel_name_for(target);
             }
         }
-        let channel = state::Channel::new(target, details.name, details.mode);
-        self.channels.insert(channel.id, channel);
+        let channel = state::Channel::new(target, details.name, details.mode, event.timestamp);
+        self.channels.insert(channel.id, channel.clone());
+
+        updates.notify(update::NewChannel { channel }, event);
+    }
+
+    pub(super) fn rename_channel(&mut self, target: ChannelId, event: &Event, details: &details::ChannelRename, updates: &dyn NetworkUpdateReceiver)
+    {
+        let mut new_name = details.new_name;
+
+        if let Ok(existing) = self.raw_channel_by_name(&new_name)
+        {
+            let existing_id = existing.id;
+
+            // Renaming a channel to a different case of its own name isn't a collision
+            if existing_id != target
+            {
+                // Resolve this the same way as for a new channel, so that the outcome doesn't
+                // depend on the order in which a creation and a rename are applied
+                if self.should_replace_channel(existing_id, target)
+                {
+                    self.rename_colliding_channel(existing_id, event, updates);
+                }
+                else
+                {
+                    new_name = state_utils::hashed_channel_name_for(target);
+                }
+            }
+        }
+
+        let source = self.translate_state_change_source(details.source);
+        self.do_rename_channel(target, new_name, source, details.message.clone(), event, updates);
     }
 
     pub(super) fn channel_mode_change(&mut self, target: ChannelId, event: &Event, details: &details::ChannelModeChange, updates: &dyn NetworkUpdateReceiver)
@@ -156,7 +196,7 @@ impl Network {
 
     pub(super) fn new_list_mode_entry(&mut self, target: ListModeEntryId, event: &Event, details: &details::NewListModeEntry, updates: &dyn NetworkUpdateReceiver)
     {
-        let setter_info = self.translate_setter_info(details.setter.into());
+        let setter_info = self.translate_setter_info(details.setter);
 
         let entry = state::ListModeEntry::new(
             target,
@@ -173,7 +213,7 @@ impl Network {
                 channel: channel.clone(),
                 list_type: details.list.list_type(),
                 pattern: details.pattern.clone(),
-                set_by: self.translate_state_change_source(details.setter.into()),
+                set_by: self.translate_state_change_source(details.setter),
             };
             updates.notify(update, event);
         }
@@ -265,6 +305,34 @@ impl Network {
         }
     }
 
+    pub(super) fn user_kicked_from_channel(&mut self, target: MembershipId, event: &Event, details: &details::ChannelKick, updates: &dyn NetworkUpdateReceiver)
+    {
+        if let Some(removed_membership) = self.memberships.remove(&target)
+        {
+            // Build the update before removing an emptied channel, so that the notification
+            // can still be sent when the last member is kicked
+            if let (Some(channel), Some(user)) = (
+                self.channels.get(&target.channel()), self.users.get(&target.user())
+            )
+            {
+                let update = update::ChannelKick {
+                    membership: removed_membership.clone(),
+                    source: self.translate_state_change_source(details.source),
+                    user: self.translate_historic_user(user.clone()),
+                    channel: channel.clone(),
+                    message: details.message.clone()
+                };
+                updates.notify(update, event);
+            }
+
+            let empty = ! self.memberships.iter().any(|(_,v)| v.channel == removed_membership.channel);
+            if empty
+            {
+                self.remove_channel(removed_membership.channel, updates);
+            }
+        }
+    }
+
     pub(super) fn new_channel_invite(&mut self, target: InviteId, event: &Event, detail: &details::ChannelInvite, updates: &dyn NetworkUpdateReceiver)
     {
         let invite = state::ChannelInvite::new(target, detail.source, event.timestamp);
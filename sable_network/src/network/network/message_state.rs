@@ -10,7 +10,8 @@ impl Network
             target: details.target,
             ts: event.timestamp,
             message_type: details.message_type,
-            text: details.text.clone()
+            text: details.text.clone(),
+            tags: details.tags.clone(),
         };
         self.messages.insert(target, message.clone());
 
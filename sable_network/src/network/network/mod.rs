@@ -204,11 +204,15 @@ impl Network {
 
         dispatch_event!(event(updates) => {
             BindNickname => self.bind_nickname,
+            ForceNickChange => self.force_nick_change,
             NewUser => self.new_user,
             UserQuit => self.user_quit,
             UserModeChange => self.user_mode_change,
+            UserAwayChange => self.user_away_change,
+            UserDetailsChange => self.user_details_change,
             OperUp => self.oper_up,
             NewChannel => self.new_channel,
+            ChannelRename => self.rename_channel,
             ChannelModeChange => self.channel_mode_change,
             NewListModeEntry => self.new_list_mode_entry,
             DelListModeEntry => self.del_list_mode_entry,
@@ -216,6 +220,7 @@ impl Network {
             MembershipFlagChange => self.channel_permission_change,
             ChannelJoin => self.user_joined_channel,
             ChannelPart => self.user_left_channel,
+            ChannelKick => self.user_kicked_from_channel,
             ChannelInvite => self.new_channel_invite,
             NewMessage => self.new_message,
             NewNetworkBan => self.new_ban,
@@ -10,7 +10,8 @@ impl Network
             let new_oper = user.oper_privileges.is_none();
 
             user.oper_privileges = Some(UserPrivileges {
-                oper_name: details.oper_name.clone()
+                oper_name: details.oper_name.clone(),
+                privileges: details.privileges,
             });
 
             user.mode.modes |= UserModeFlag::Oper;
@@ -160,6 +160,17 @@ impl Network {
         }
     }
 
+    pub(super) fn force_nick_change(&mut self, target: NicknameId, event: &Event, detail: &details::ForceNickChange, updates: &dyn NetworkUpdateReceiver)
+    {
+        // The user may have changed nick, or someone else bound this one, since the event was
+        // emitted; only move them if they still hold it
+        if self.nick_bindings.get(target.nick()).map(|b| b.user) == Some(detail.user)
+        {
+            self.nick_bindings.remove(target.nick());
+            self.collide_user(detail.user, *target.nick(), event, updates);
+        }
+    }
+
     pub(super) fn new_user(&mut self, target: UserId, event: &Event, detail: &details::NewUser, updates: &dyn NetworkUpdateReceiver)
     {
         let user = state::User::new(target,
@@ -216,6 +227,55 @@ impl Network {
         }
     }
 
+    pub(super) fn user_away_change(&mut self, target: UserId, event: &Event, detail: &details::UserAwayChange, updates: &dyn NetworkUpdateReceiver)
+    {
+        if let Some(user) = self.users.get_mut(&target)
+        {
+            let old_reason = std::mem::replace(&mut user.away_reason, detail.reason.clone());
+            user.auto_away = detail.automatic && detail.reason.is_some();
+
+            let update_user = user.clone();
+
+            updates.notify(update::UserAwayChange {
+                user: self.translate_historic_user(update_user),
+                old_reason,
+                new_reason: detail.reason.clone(),
+            }, event);
+        }
+    }
+
+    pub(super) fn user_details_change(&mut self, target: UserId, event: &Event, detail: &details::UserDetailsChange, updates: &dyn NetworkUpdateReceiver)
+    {
+        if let Some(user) = self.users.get_mut(&target)
+        {
+            let old_username = user.user;
+            let old_visible_host = user.visible_host;
+            let old_realname = user.realname.clone();
+
+            if let Some(username) = detail.username
+            {
+                user.user = username;
+            }
+            if let Some(visible_host) = detail.visible_host
+            {
+                user.visible_host = visible_host;
+            }
+            if let Some(realname) = &detail.realname
+            {
+                user.realname = realname.clone();
+            }
+
+            let update_user = user.clone();
+
+            updates.notify(update::UserDetailsChange {
+                user: self.translate_historic_user(update_user),
+                old_username,
+                old_visible_host,
+                old_realname,
+            }, event);
+        }
+    }
+
     pub(super) fn user_quit(&mut self, target: UserId, event: &Event, quit: &details::UserQuit, updates: &dyn NetworkUpdateReceiver)
     {
         if let Some(update) = self.remove_user(target, quit.message.clone())
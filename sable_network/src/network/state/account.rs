@@ -27,6 +27,34 @@ pub struct ChannelRegistration
 {
     pub id: ChannelRegistrationId,
     pub channelname: ChannelName,
+
+    /// Topic to be set whenever the channel is recreated, and restored if changed
+    #[serde(default)]
+    pub topic_lock: Option<String>,
+    /// Modes to be set or unset whenever the channel is recreated, and enforced afterwards
+    #[serde(default)]
+    pub mode_lock: Option<ChannelModeLock>,
+    /// Notice sent to users joining the channel
+    #[serde(default)]
+    pub entry_message: Option<String>,
+    /// List mode entries to be restored whenever the channel is recreated
+    #[serde(default)]
+    pub list_entries: Vec<PersistentListEntry>,
+}
+
+#[derive(PartialEq,Debug,Clone,Serialize,Deserialize)]
+pub struct ChannelModeLock
+{
+    pub added: ChannelModeSet,
+    pub removed: ChannelModeSet,
+    pub key: Option<ChannelKey>,
+}
+
+#[derive(PartialEq,Debug,Clone,Serialize,Deserialize)]
+pub struct PersistentListEntry
+{
+    pub list_type: ListModeType,
+    pub pattern: Pattern,
 }
 
 #[derive(PartialEq,Debug,Clone,Serialize,Deserialize)]
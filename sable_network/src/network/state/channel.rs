@@ -11,6 +11,9 @@ pub struct Channel {
     pub id: ChannelId,
     pub name: ChannelName,
     pub mode: ChannelMode,
+    /// Creation timestamp, or zero for channels created before this was recorded
+    #[serde(default)]
+    pub created: i64,
 }
 
 /// A channel membership
@@ -59,9 +62,9 @@ pub struct ChannelInvite {
 }
 
 impl Channel {
-    pub fn new(id: ChannelId, name: ChannelName, mode: ChannelMode) -> Self
+    pub fn new(id: ChannelId, name: ChannelName, mode: ChannelMode, created: i64) -> Self
     {
-        Channel { id, name, mode }
+        Channel { id, name, mode, created }
     }
 }
 
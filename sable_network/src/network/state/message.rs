@@ -5,12 +5,22 @@ use serde::{
     Deserialize
 };
 
-/// Message type - privmsg or notice
-#[derive(Debug,Clone,Copy,Serialize,Deserialize)]
+/// Message type - privmsg, notice, or a tag-only message
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Serialize,Deserialize)]
 pub enum MessageType
 {
     Privmsg,
-    Notice
+    Notice,
+    Tagmsg,
+}
+
+/// A message tag supplied by a client. Those attached to a [`Message`] are client-only
+/// tags (whose names begin with `+`), relayed unchanged to recipients
+#[derive(Debug,Clone,PartialEq,Eq,Serialize,Deserialize)]
+pub struct ClientTag
+{
+    pub name: String,
+    pub value: Option<String>,
 }
 
 /// A message
@@ -23,6 +33,8 @@ pub struct Message
     pub ts: i64,
     pub message_type: MessageType,
     pub text: String,
+    #[serde(default)]
+    pub tags: Vec<ClientTag>,
 }
 
 impl std::fmt::Display for MessageType
@@ -32,7 +44,8 @@ impl std::fmt::Display for MessageType
         match self
         {
             Self::Privmsg => "PRIVMSG".fmt(f),
-            Self::Notice => "NOTICE".fmt(f)
+            Self::Notice => "NOTICE".fmt(f),
+            Self::Tagmsg => "TAGMSG".fmt(f),
         }
     }
 }
\ No newline at end of file
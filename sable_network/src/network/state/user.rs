@@ -4,6 +4,7 @@ use serde::{
     Serialize,
     Deserialize
 };
+use strum::IntoEnumIterator;
 
 /// A nickname binding.
 ///
@@ -39,6 +40,11 @@ pub struct User {
     pub account: Option<AccountId>,
 
     pub session_key: Option<UserSessionKey>,
+
+    pub away_reason: Option<String>,
+    /// Whether the away status was set automatically rather than by the user
+    #[serde(default)]
+    pub auto_away: bool,
 }
 
 /// A persistent session key. If present on a [`User`], then that user's session
@@ -59,10 +65,80 @@ pub struct UserMode {
     pub modes: UserModeSet,
 }
 
+/// A privilege which can be granted to an oper by their oper class
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash,Serialize,Deserialize)]
+#[derive(strum::EnumString, strum::EnumIter, strum::Display)]
+#[strum(serialize_all="snake_case")]
+#[serde(rename_all="snake_case")]
+#[repr(u32)]
+pub enum OperPrivilege {
+    Kline           = 0x01,
+    Kill            = 0x02,
+    Chghost         = 0x04,
+    SeeSecret       = 0x08,
+    AuditRead       = 0x10,
+    ServicesAdmin   = 0x20,
+}
+
+/// A set of [`OperPrivilege`]s. Serialised as a list of privilege names.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default,Serialize,Deserialize)]
+#[serde(from="Vec<OperPrivilege>", into="Vec<OperPrivilege>")]
+pub struct OperPrivilegeSet(u32);
+
+impl OperPrivilegeSet
+{
+    pub fn new() -> Self
+    {
+        Self(0)
+    }
+
+    /// Determine whether the given privilege is in this set
+    pub fn has(&self, privilege: OperPrivilege) -> bool
+    {
+        self.0 & privilege as u32 != 0
+    }
+
+    /// Add a privilege to this set
+    pub fn set(&mut self, privilege: OperPrivilege)
+    {
+        self.0 |= privilege as u32;
+    }
+
+    /// Iterate over the privileges in this set
+    pub fn iter(&self) -> impl Iterator<Item=OperPrivilege> + '_
+    {
+        OperPrivilege::iter().filter(move |p| self.has(*p))
+    }
+}
+
+impl From<Vec<OperPrivilege>> for OperPrivilegeSet
+{
+    fn from(privileges: Vec<OperPrivilege>) -> Self
+    {
+        let mut ret = Self::new();
+        for p in privileges
+        {
+            ret.set(p);
+        }
+        ret
+    }
+}
+
+impl From<OperPrivilegeSet> for Vec<OperPrivilege>
+{
+    fn from(set: OperPrivilegeSet) -> Self
+    {
+        set.iter().collect()
+    }
+}
+
 /// A user's operator privileges
 #[derive(Debug,Clone,Serialize,Deserialize)]
 pub struct UserPrivileges {
     pub oper_name: String,
+    /// Opers from before privilege classes were introduced have no privileges
+    #[serde(default)]
+    pub privileges: OperPrivilegeSet,
 }
 
 impl NickBinding
@@ -89,6 +165,8 @@ impl User {
             oper_privileges: None,
             account,
             session_key: None,
+            away_reason: None,
+            auto_away: false,
         }
     }
 }
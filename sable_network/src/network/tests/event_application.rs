@@ -1,5 +1,6 @@
 use crate::prelude::*;
 use super::fixtures::*;
+use event::*;
 use std::str::FromStr;
 
 #[test]
@@ -15,3 +16,107 @@ fn add_and_remove_user()
 
     assert_eq!(empty_net, modified_net);
 }
+
+#[test]
+fn force_nick_change_ignores_stale_events()
+{
+    let mut builder = NetworkBuilder::new();
+    let nick_a = Nickname::from_str("aaa").unwrap();
+    let nick_b = Nickname::from_str("bbb").unwrap();
+    builder.add_user(nick_a);
+    builder.add_user(nick_b);
+    let id_a = builder.net.user_by_nick(&nick_a).unwrap().id();
+    let id_b = builder.net.user_by_nick(&nick_b).unwrap().id();
+
+    // An enforcement event naming a user who no longer holds the nick moves nobody
+    builder.apply(NicknameId::new(nick_a), details::ForceNickChange { user: id_b });
+    assert_eq!(builder.net.user_by_nick(&nick_a).unwrap().id(), id_a);
+    assert_eq!(builder.net.user_by_nick(&nick_b).unwrap().id(), id_b);
+
+    builder.apply(NicknameId::new(nick_a), details::ForceNickChange { user: id_a });
+    assert!(builder.net.user_by_nick(&nick_a).is_err());
+    assert_eq!(builder.net.user(id_a).unwrap().nick(), crate::network::state_utils::hashed_nick_for(id_a));
+}
+
+#[test]
+fn automatic_away()
+{
+    let mut builder = NetworkBuilder::new();
+    let nick = Nickname::from_str("aaa").unwrap();
+    builder.add_user(nick);
+    let user_id = builder.net.user_by_nick(&nick).unwrap().id();
+
+    builder.apply(user_id, details::UserAwayChange { reason: Some("Detached".to_string()), automatic: true });
+    assert!(builder.net.user(user_id).unwrap().is_auto_away());
+
+    // Setting an away message by hand replaces the automatic one, so it won't be cleared on reattach
+    builder.apply(user_id, details::UserAwayChange { reason: Some("gone".to_string()), automatic: false });
+    let user = builder.net.user(user_id).unwrap();
+    assert_eq!(user.away_reason(), Some("gone"));
+    assert!(!user.is_auto_away());
+
+    builder.apply(user_id, details::UserAwayChange { reason: Some("Detached".to_string()), automatic: true });
+    builder.apply(user_id, details::UserAwayChange { reason: None, automatic: false });
+    let user = builder.net.user(user_id).unwrap();
+    assert_eq!(user.away_reason(), None);
+    assert!(!user.is_auto_away());
+}
+
+#[test]
+fn user_details_change()
+{
+    let mut builder = NetworkBuilder::new();
+    let nick = Nickname::from_str("aaa").unwrap();
+    builder.add_user(nick);
+    let user_id = builder.net.user_by_nick(&nick).unwrap().id();
+
+    let updates = builder.apply(user_id, details::UserDetailsChange {
+        username: Some(Username::from_str("b").unwrap()),
+        visible_host: Some(Hostname::from_str("cloak.example").unwrap()),
+        realname: Some("new name".to_owned()),
+    });
+
+    let user = builder.net.user(user_id).unwrap();
+    assert_eq!(user.user().value().as_str(), "b");
+    assert_eq!(user.visible_host().value().as_str(), "cloak.example");
+    assert_eq!(user.realname(), "new name");
+
+    let [NetworkStateChange::UserDetailsChange(update)] = updates.as_slice() else {
+        panic!("expected a single UserDetailsChange, got {:?}", updates);
+    };
+    assert_eq!(update.old_username.value().as_str(), "a");
+    assert_eq!(update.old_visible_host.value().as_str(), "host.name");
+    assert_eq!(update.old_realname, "user");
+    assert_eq!(update.user.user.realname, "new name");
+
+    // Fields which aren't given are left alone
+    builder.apply(user_id, details::UserDetailsChange { username: None, visible_host: None, realname: Some("other".to_owned()) });
+    let user = builder.net.user(user_id).unwrap();
+    assert_eq!(user.visible_host().value().as_str(), "cloak.example");
+    assert_eq!(user.realname(), "other");
+}
+
+#[test]
+fn rename_channel_collision()
+{
+    let mut builder = NetworkBuilder::new();
+    let name_a = ChannelName::from_str("#aaa").unwrap();
+    let name_b = ChannelName::from_str("#bbb").unwrap();
+    builder.add_channel(name_a);
+    builder.add_channel(name_b);
+    let id_a = builder.net.channel_by_name(&name_a).unwrap().id();
+    let id_b = builder.net.channel_by_name(&name_b).unwrap().id();
+
+    let rename = |new_name| details::ChannelRename { source: ServerId::new(1).into(), new_name, message: "rename".to_string() };
+
+    // The channel with the lower ID wins, and the existing #bbb is moved aside
+    builder.apply(id_a, rename(name_b));
+    assert!(builder.net.channel_by_name(&name_a).is_err());
+    assert_eq!(builder.net.channel_by_name(&name_b).unwrap().id(), id_a);
+    assert_ne!(*builder.net.channel(id_b).unwrap().name(), name_b);
+
+    // Renaming the loser back onto the winner's name leaves the winner in place
+    builder.apply(id_b, rename(name_b));
+    assert_eq!(builder.net.channel_by_name(&name_b).unwrap().id(), id_a);
+    assert_ne!(*builder.net.channel(id_b).unwrap().name(), name_b);
+}
@@ -8,12 +8,19 @@ pub struct NetworkBuilder
     id_gen: ObjectIdGenerator,
 }
 
-struct NopUpdateReceiver;
+/// Collects the updates emitted while applying an event
+#[derive(Default)]
+struct RecordingUpdateReceiver
+{
+    updates: std::cell::RefCell<Vec<NetworkStateChange>>,
+}
 
-impl NetworkUpdateReceiver for NopUpdateReceiver
+impl NetworkUpdateReceiver for RecordingUpdateReceiver
 {
-    fn notify_update(&self, _update: NetworkStateChange, _event: &Event)
-    { }
+    fn notify_update(&self, update: NetworkStateChange, _event: &Event)
+    {
+        self.updates.borrow_mut().push(update);
+    }
 }
 
 impl NetworkBuilder
@@ -33,7 +40,8 @@ impl NetworkBuilder
         json
     }
 
-    fn apply(&mut self, target: impl Into<ObjectId>, details: impl Into<EventDetails>)
+    /// Apply an event to the network, returning the updates it produced
+    pub fn apply(&mut self, target: impl Into<ObjectId>, details: impl Into<EventDetails>) -> Vec<NetworkStateChange>
     {
         let evt = Event {
             clock: EventClock::new(),
@@ -42,7 +50,9 @@ impl NetworkBuilder
             timestamp: 0,
             details: details.into()
         };
-        self.net.apply(&evt, &NopUpdateReceiver).unwrap();
+        let receiver = RecordingUpdateReceiver::default();
+        self.net.apply(&evt, &receiver).unwrap();
+        receiver.updates.into_inner()
     }
 
     pub fn add_channel(&mut self, name: ChannelName)
@@ -66,8 +76,18 @@ impl NetworkBuilder
             });
     }
 
+    /// Create an account with the given name and log the user in to it
+    pub fn log_in(&mut self, user: UserId, account_name: Nickname)
+    {
+        let account = self.id_gen.next_account();
+        self.apply(account, details::AccountUpdate {
+                data: Some(state::Account { id: account, name: account_name, authorised_fingerprints: Vec::new() })
+            });
+        self.apply(user, details::UserLogin { account: Some(account) });
+    }
+
     pub fn remove_user(&mut self, id: UserId)
     {
-        self.apply(id, details::UserQuit { message: "quit".to_string() })
+        self.apply(id, details::UserQuit { message: "quit".to_string() });
     }
 }
\ No newline at end of file
@@ -31,4 +31,46 @@ fn net_with_user_can_be_serialized()
     let net: Network = serde_json::from_str(&str).unwrap();
     assert_eq!(net.channels().count(), 0);
     assert_eq!(net.users().count(), 1);
-}
\ No newline at end of file
+}
+#[test]
+fn channel_without_creation_time_can_be_deserialized()
+{
+    let mut builder = NetworkBuilder::new();
+    builder.add_channel(ChannelName::from_str("#a").unwrap());
+    let mut json = serde_json::to_value(&builder.net).unwrap();
+
+    // State saved by a version which didn't record channel creation times
+    for entry in json["channels"].as_array_mut().unwrap()
+    {
+        entry[1].as_object_mut().unwrap().remove("created").unwrap();
+    }
+
+    let net: Network = serde_json::from_value(json).unwrap();
+    assert_eq!(net.channels().next().unwrap().created(), 0);
+}
+
+#[test]
+fn oper_without_privileges_can_be_deserialized()
+{
+    let mut builder = NetworkBuilder::new();
+    let nick = Nickname::from_str("a").unwrap();
+    builder.add_user(nick);
+    let user_id = builder.net.user_by_nick(&nick).unwrap().id();
+    builder.apply(user_id, event::details::OperUp { oper_name: "oper".to_string(), privileges: vec![state::OperPrivilege::Kill].into() });
+    let mut json = serde_json::to_value(&builder.net).unwrap();
+
+    // State saved by a version which didn't have oper privilege classes
+    for entry in json["users"].as_array_mut().unwrap()
+    {
+        entry[1]["oper_privileges"].as_object_mut().unwrap().remove("privileges").unwrap();
+    }
+
+    let net: Network = serde_json::from_value(json).unwrap();
+    let user = net.user(user_id).unwrap();
+    assert!(user.is_oper());
+    assert_eq!(user.oper_privileges().unwrap().privileges, state::OperPrivilegeSet::default());
+
+    // Likewise for an OperUp event from a server running such a version
+    let event: event::details::OperUp = serde_json::from_str(r#"{"oper_name":"oper"}"#).unwrap();
+    assert_eq!(event.privileges, state::OperPrivilegeSet::default());
+}
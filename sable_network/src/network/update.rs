@@ -74,6 +74,21 @@ NetworkStateChange => {
         pub changed_by: HistoricMessageSource,
     }
 
+    /// A user has set or cleared their away status
+    struct UserAwayChange {
+        pub user: HistoricUser,
+        pub old_reason: Option<String>,
+        pub new_reason: Option<String>,
+    }
+
+    /// A user's username, visible hostname or realname has changed
+    struct UserDetailsChange {
+        pub user: HistoricUser,
+        pub old_username: Username,
+        pub old_visible_host: Hostname,
+        pub old_realname: String,
+    }
+
     /// A user has left the network
     struct UserQuit {
         pub user: HistoricUser,
@@ -87,6 +102,11 @@ NetworkStateChange => {
         pub items: Vec<UserQuit>,
     }
 
+    /// A channel has been created
+    struct NewChannel {
+        pub channel: state::Channel,
+    }
+
     /// A channel's mode has changed
     struct ChannelModeChange {
         pub channel: state::Channel,
@@ -146,6 +166,15 @@ NetworkStateChange => {
         pub message: String,
     }
 
+    /// A user has been kicked from a channel
+    struct ChannelKick {
+        pub membership: state::Membership,
+        pub source: HistoricMessageSource,
+        pub user: HistoricUser,
+        pub channel: state::Channel,
+        pub message: String,
+    }
+
     /// A user has been invited to a channel
     struct ChannelInvite {
         pub invite: state::ChannelInvite,
@@ -157,8 +186,10 @@ NetworkStateChange => {
     /// A channel's name has changed
     struct ChannelRename {
         pub channel: state::Channel,
+        pub source: HistoricMessageSource,
         pub old_name: ChannelName,
         pub new_name: ChannelName,
+        pub message: String,
     }
 
     /// A message has been sent to a user or channel
@@ -18,6 +18,11 @@ impl<'a> Channel<'a> {
         &self.data.name
     }
 
+    /// The time at which this channel was created, or zero if it isn't known
+    pub fn created(&self) -> i64 {
+        self.data.created
+    }
+
     /// The [ChannelMode] for this channel
     pub fn mode(&self) -> ChannelMode {
         ChannelMode::wrap(self.network, &self.data.mode)
@@ -18,6 +18,30 @@ impl ChannelRegistration<'_>
         &self.data.channelname
     }
 
+    /// The topic locked on the channel, set when it is created and restored whenever it changes
+    pub fn topic_lock(&self) -> Option<&str>
+    {
+        self.data.topic_lock.as_deref()
+    }
+
+    /// The modes locked on or off the channel, set when it is created and enforced on every change
+    pub fn mode_lock(&self) -> Option<&state::ChannelModeLock>
+    {
+        self.data.mode_lock.as_ref()
+    }
+
+    /// The notice to be sent to users joining the channel, if any
+    pub fn entry_message(&self) -> Option<&str>
+    {
+        self.data.entry_message.as_deref()
+    }
+
+    /// The list mode entries to be restored when the channel is recreated
+    pub fn list_entries(&self) -> &[state::PersistentListEntry]
+    {
+        &self.data.list_entries
+    }
+
     pub fn access_entries(&self) -> impl Iterator<Item=ChannelAccess>
     {
         let my_id = self.data.id;
@@ -39,7 +39,7 @@ impl Message<'_> {
         }
     }
 
-    /// Whether this is a privmsg or a notice
+    /// Whether this is a privmsg, notice or tag-only message
     pub fn message_type(&self) -> state::MessageType
     {
         self.data.message_type
@@ -51,6 +51,12 @@ impl Message<'_> {
         &self.data.text
     }
 
+    /// The client-only tags attached to the message
+    pub fn tags(&self) -> &[state::ClientTag]
+    {
+        &self.data.tags
+    }
+
     /// The message's timestamp
     pub fn ts(&self) -> i64
     {
@@ -1,7 +1,7 @@
 use crate::prelude::*;
 
 pub struct NickRegistration<'a> {
-    _network: &'a Network,
+    network: &'a Network,
     data: &'a state::NickRegistration,
 }
 
@@ -11,6 +11,16 @@ impl NickRegistration<'_>
     {
         self.data.id
     }
+
+    pub fn nick(&self) -> Nickname
+    {
+        self.data.nick
+    }
+
+    pub fn account(&self) -> LookupResult<wrapper::Account>
+    {
+        self.network.account(self.data.account)
+    }
 }
 
 impl<'a> super::ObjectWrapper<'a> for NickRegistration<'a> {
@@ -18,7 +28,7 @@ impl<'a> super::ObjectWrapper<'a> for NickRegistration<'a> {
 
     fn wrap(net: &'a Network, data: &'a Self::Underlying) -> Self
     {
-        Self{ _network: net, data }
+        Self{ network: net, data }
     }
 
     fn raw(&self) -> &'a Self::Underlying { self.data }
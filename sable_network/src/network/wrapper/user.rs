@@ -94,6 +94,19 @@ impl<'a> User<'a> {
         self.data.session_key.as_ref()
     }
 
+    /// Return the user's away message, if they are marked as away
+    pub fn away_reason(&self) -> Option<&str>
+    {
+        self.data.away_reason.as_deref()
+    }
+
+    /// Whether the user's away status was set automatically when their last connection
+    /// detached from a persistent session
+    pub fn is_auto_away(&self) -> bool
+    {
+        self.data.auto_away
+    }
+
     /// Return the user's account, if any
     pub fn account(&self) -> LookupResult<Option<super::Account<'a>>>
     {
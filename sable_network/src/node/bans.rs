@@ -0,0 +1,18 @@
+use super::*;
+
+impl<Policy: crate::policy::PolicyService> NetworkNode<Policy>
+{
+    pub(super) fn check_ban_expiry(&self)
+    {
+        let now = utils::now();
+
+        for ban in self.net.read().network_bans().iter()
+        {
+            if ban.expires <= now
+            {
+                let remove_detail = details::RemoveNetworkBan { remover: self.my_id.into() };
+                self.submit_event(ban.id, remove_detail);
+            }
+        }
+    }
+}
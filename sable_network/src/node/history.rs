@@ -45,4 +45,24 @@ impl<Policy: crate::policy::PolicyService> NetworkNode<Policy>
     {
         self.notify_users(channel.members().filter(predicate).map(|m| m.user_id()), entry.id);
     }
-}
\ No newline at end of file
+
+    /// Record a user's details in the WHOWAS index, when they stop using the given nickname
+    pub(super) fn record_whowas(&self, nickname: Nickname, user: &state::User, timestamp: i64)
+    {
+        let net = self.network();
+
+        let entry = WhowasEntry {
+            nickname,
+            user: user.clone(),
+            server_name: net.server(user.server).ok().map(|s| *s.name()),
+            timestamp,
+        };
+
+        self.whowas.write().add(entry, &net.config().whowas);
+    }
+
+    pub(super) fn expire_whowas(&self)
+    {
+        self.whowas.write().expire(&self.network().config().whowas, utils::now());
+    }
+}
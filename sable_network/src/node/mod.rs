@@ -33,6 +33,7 @@ use parking_lot::{
 };
 
 mod pings;
+mod bans;
 mod update_receiver;
 mod history;
 
@@ -55,6 +56,7 @@ pub struct NetworkNode<Policy = crate::policy::StandardPolicyService>
     // This needs to be a tokio mutex because we hold it for the duration of `run()`, which awaits a lot
     rpc_receiver: tokio::sync::Mutex<UnboundedReceiver<NetworkMessage>>,
     history_log: RwLock<NetworkHistoryLog>,
+    whowas: RwLock<WhowasIndex>,
     subscriber: UnboundedSender<NetworkHistoryUpdate>,
     remote_server_commands: Option<UnboundedSender<RemoteServerRequest>>,
     policy_service: Policy,
@@ -105,6 +107,7 @@ impl<Policy: crate::policy::PolicyService> NetworkNode<Policy>
             id_generator: ObjectIdGenerator::new(id, epoch),
             rpc_receiver: Mutex::new(rpc_receiver),
             history_log: RwLock::new(NetworkHistoryLog::new()),
+            whowas: RwLock::new(WhowasIndex::new()),
             subscriber,
             remote_server_commands,
             policy_service,
@@ -147,6 +150,12 @@ impl<Policy: crate::policy::PolicyService> NetworkNode<Policy>
         self.history_log.read()
     }
 
+    /// Access the WHOWAS index
+    pub fn whowas(&self) -> RwLockReadGuard<WhowasIndex>
+    {
+        self.whowas.read()
+    }
+
     /// Access the event log.
     pub fn event_log(&self) -> std::sync::RwLockReadGuard<EventLog>
     {
@@ -233,6 +242,8 @@ impl<Policy: crate::policy::PolicyService> NetworkNode<Policy>
         });
 
         let mut check_ping_timer = time::interval(Duration::from_secs(60));
+        let mut check_bans_timer = time::interval(Duration::from_secs(60));
+        let mut expire_whowas_timer = time::interval(Duration::from_secs(300));
 
         let mut rpc_receiver = self.rpc_receiver.lock().await;
 
@@ -284,6 +295,16 @@ impl<Policy: crate::policy::PolicyService> NetworkNode<Policy>
                     tracing::trace!("...from check_ping_timer");
                     self.check_pings();
                 },
+                _ = check_bans_timer.tick() =>
+                {
+                    tracing::trace!("...from check_bans_timer");
+                    self.check_ban_expiry();
+                },
+                _ = expire_whowas_timer.tick() =>
+                {
+                    tracing::trace!("...from expire_whowas_timer");
+                    self.expire_whowas();
+                },
                 shutdown = shutdown_channel.recv() =>
                 {
                     match shutdown
@@ -21,12 +21,32 @@ pub enum HandlerError
 
 pub type HandleResult = Result<(), HandlerError>;
 
+/// Find every user who shares at least one channel with the given user, including the
+/// user themselves if they are in any channels
+fn channel_peers(user: &wrapper::User) -> Result<HashSet<UserId>, HandlerError>
+{
+    let mut peers = HashSet::new();
+
+    for membership in user.channels()
+    {
+        let chan = membership.channel()?;
+        for m2 in chan.members()
+        {
+            peers.insert(m2.user_id());
+        }
+    }
+
+    Ok(peers)
+}
+
 impl<Policy: crate::policy::PolicyService> NetworkNode<Policy>
 {
     fn handle_nick_change(&self, entry: &HistoryLogEntry, detail: &update::UserNickChange) -> HandleResult
     {
         // This fires after the nick change is applied to the network state, so we
         // have to construct the n!u@h string explicitly
+        self.record_whowas(detail.old_nick, &detail.user, entry.timestamp);
+
         let net = self.network();
         let source = net.user(detail.user.id)?;
         let mut notified = HashSet::new();
@@ -53,8 +73,38 @@ impl<Policy: crate::policy::PolicyService> NetworkNode<Policy>
         Ok(())
     }
 
+    fn handle_away_change(&self, entry: &HistoryLogEntry, detail: &update::UserAwayChange) -> HandleResult
+    {
+        // Away changes are shown to users who share a channel, but not to the user themselves
+        let net = self.network();
+        let source = net.user(detail.user.user.id)?;
+        let mut notified = channel_peers(&source)?;
+
+        notified.remove(&source.id());
+
+        self.notify_users(notified, entry.id);
+
+        Ok(())
+    }
+
+    fn handle_details_change(&self, entry: &HistoryLogEntry, detail: &update::UserDetailsChange) -> HandleResult
+    {
+        // Detail changes are shown to the user themselves and to users who share a channel
+        let net = self.network();
+        let source = net.user(detail.user.user.id)?;
+        let mut notified = channel_peers(&source)?;
+
+        notified.insert(source.id());
+
+        self.notify_users(notified, entry.id);
+
+        Ok(())
+    }
+
     fn handle_user_quit(&self, entry: &HistoryLogEntry, detail: &update::UserQuit) -> HandleResult
     {
+        self.record_whowas(detail.nickname, &detail.user.user, entry.timestamp);
+
         let net = self.network();
 
         let mut notified = HashSet::new();
@@ -158,6 +208,18 @@ impl<Policy: crate::policy::PolicyService> NetworkNode<Policy>
         Ok(())
     }
 
+    fn handle_kick(&self, entry: &HistoryLogEntry, detail: &update::ChannelKick) -> HandleResult
+    {
+        self.notify_user(detail.user.user.id, entry.id);
+
+        let network = self.network();
+        let channel = wrapper::Channel::wrap(&*network, &detail.channel);
+
+        self.notify_channel_members(&channel, entry);
+
+        Ok(())
+    }
+
     fn handle_invite(&self, entry: &HistoryLogEntry, detail: &update::ChannelInvite) -> HandleResult
     {
         self.notify_user(detail.user.user.id, entry.id);
@@ -268,14 +330,18 @@ impl<Policy: crate::policy::PolicyService> NetworkUpdateReceiver for NetworkNode
             NewUser(_details) => Ok(()),
             UserNickChange(details) => self.handle_nick_change(entry, details),
             UserModeChange(details) => self.handle_umode_change(entry, details),
+            UserAwayChange(details) => self.handle_away_change(entry, details),
+            UserDetailsChange(details) => self.handle_details_change(entry, details),
             UserQuit(details) => self.handle_user_quit(entry, details),
             BulkUserQuit(details) => self.handle_bulk_quit(&history_guard, entry, details),
+            NewChannel(_details) => Ok(()),
             ChannelModeChange(details) => self.handle_channel_mode_change(entry, details),
             ListModeAdded(details) => self.handle_list_mode_added(entry, details),
             ListModeRemoved(details) => self.handle_list_mode_removed(entry, details),
             ChannelTopicChange(details) => self.handle_channel_topic(entry, details),
             ChannelJoin(details) => self.handle_join(entry, details),
             ChannelPart(details) => self.handle_part(entry, details),
+            ChannelKick(details) => self.handle_kick(entry, details),
             ChannelInvite(details) => self.handle_invite(entry, details),
             ChannelRename(details) => self.handle_channel_rename(entry, details),
             MembershipFlagChange(details) => self.handle_chan_perm_change(entry, details),
@@ -291,4 +357,50 @@ impl<Policy: crate::policy::PolicyService> NetworkUpdateReceiver for NetworkNode
             tracing::error!("Error ({}) handling state update {:?}", e, entry.details);
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::network::tests::fixtures::NetworkBuilder;
+    use std::str::FromStr;
+
+    #[test]
+    fn channel_peers_spans_shared_channels()
+    {
+        let mut builder = NetworkBuilder::new();
+        let mut users = Vec::new();
+        for nick in ["aaa", "bbb", "ccc", "ddd"]
+        {
+            let nick = Nickname::from_str(nick).unwrap();
+            builder.add_user(nick);
+            users.push(builder.net.user_by_nick(&nick).unwrap().id());
+        }
+        let mut channels = Vec::new();
+        for name in ["#one", "#two"]
+        {
+            let name = ChannelName::from_str(name).unwrap();
+            builder.add_channel(name);
+            channels.push(builder.net.channel_by_name(&name).unwrap().id());
+        }
+
+        // aaa shares #one with bbb and #two with ccc; ddd is in no channels
+        for (user, channel) in [(users[0], channels[0]), (users[1], channels[0]), (users[0], channels[1]), (users[2], channels[1])]
+        {
+            builder.apply(MembershipId::new(user, channel), event::details::ChannelJoin {
+                channel,
+                user,
+                permissions: MembershipFlagSet::new(),
+            });
+        }
+
+        let peers = channel_peers(&builder.net.user(users[0]).unwrap()).unwrap();
+        assert_eq!(peers, HashSet::from([users[0], users[1], users[2]]));
+
+        let peers = channel_peers(&builder.net.user(users[1]).unwrap()).unwrap();
+        assert_eq!(peers, HashSet::from([users[0], users[1]]));
+
+        assert!(channel_peers(&builder.net.user(users[3]).unwrap()).unwrap().is_empty());
+    }
+}
@@ -11,6 +11,8 @@ pub struct NetworkNodeState<Policy = crate::policy::StandardPolicyService>
     epoch: EpochId,
     id_generator: ObjectIdGenerator,
     history_log: NetworkHistoryLog,
+    #[serde(default)]
+    whowas_state: <WhowasIndex as Saveable>::Saved,
     policy_state: Policy::Saved,
 }
 
@@ -26,6 +28,7 @@ impl<Policy: PolicyService + Saveable> NetworkNode<Policy>
             epoch: self.epoch,
             id_generator: self.id_generator,
             history_log: self.history_log.into_inner(),
+            whowas_state: self.whowas.into_inner().save(),
             policy_state: self.policy_service.save(),
         }
     }
@@ -52,6 +55,7 @@ impl<Policy: PolicyService + Saveable> NetworkNode<Policy>
             event_log,
             rpc_receiver: Mutex::new(rpc_receiver),
             history_log: RwLock::new(state.history_log),
+            whowas: RwLock::new(WhowasIndex::restore(state.whowas_state)),
             subscriber,
             policy_service: Policy::restore(state.policy_state),
             remote_server_commands,
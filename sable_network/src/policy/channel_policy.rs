@@ -21,6 +21,9 @@ pub trait ChannelPolicyService
     fn can_grant_permission(&self, user: &User, channel: &Channel, target: &User, flag: MembershipFlagFlag) -> PermissionResult;
     /// Determine whether the given user can remove a channel privilege flag from the given target user
     fn can_remove_permission(&self, user: &User, channel: &Channel, target: &User, flag: MembershipFlagFlag) -> PermissionResult;
+    /// Determine which channel privilege flags the given user should be granted automatically
+    /// on joining the given channel, or on logging in while already a member
+    fn auto_permissions(&self, user: &User, channel: &Channel) -> MembershipFlagSet;
 
     /// Determine whether the given string is a valid ban mask for the given channel
     fn validate_ban_mask(&self, mask: &str, mode_type: ListModeType, channel: &Channel) -> PermissionResult;
@@ -38,4 +41,8 @@ pub trait ChannelPolicyService
     fn can_set_key(&self, user: &User, chan: &Channel, new_key: Option<&ChannelKey>) -> PermissionResult;
     /// Determine whether the given user can invite the given target to a channel
     fn can_invite(&self, user: &User, chan: &Channel, target: &User) -> PermissionResult;
+    /// Determine whether the given user can kick the given target from a channel
+    fn can_kick(&self, user: &User, chan: &Channel, target: &User) -> PermissionResult;
+    /// Determine whether the given user can rename a channel to the given new name
+    fn can_rename(&self, user: &User, chan: &Channel, new_name: &ChannelName) -> PermissionResult;
 }
@@ -1,10 +1,40 @@
 use super::*;
 
+/// Reasons for which an attempt to gain oper access can be refused
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum OperAuthenticationFailure
+{
+    /// The oper name or password didn't match
+    BadPassword,
+    /// The oper block requires a client certificate which wasn't presented
+    FingerprintMismatch,
+    /// The oper block requires the user to be logged in to a particular account
+    AccountMismatch,
+}
+
+impl std::fmt::Display for OperAuthenticationFailure
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        match self
+        {
+            Self::BadPassword => f.write_str("Incorrect password"),
+            Self::FingerprintMismatch => f.write_str("Client certificate fingerprint does not match"),
+            Self::AccountMismatch => f.write_str("Not logged in to the required account"),
+        }
+    }
+}
+
 /// Makes authentication decisions for users attempting to gain oper access
 #[delegatable_trait]
 pub trait OperAuthenticationService
 {
-    fn authenticate(&self, oper_config: &crate::network::config::OperConfig, user: &str, pass: &str) -> bool;
+    /// Check the given user's credentials against an oper block. `fingerprint` is that of the
+    /// TLS client certificate presented by the user's connection, if any.
+    ///
+    /// Every requirement set in the oper block must be met for authentication to succeed.
+    fn authenticate(&self, oper_config: &crate::network::config::OperConfig, user: &wrapper::User,
+                    oper_name: &str, pass: &str, fingerprint: Option<&str>) -> Result<(), OperAuthenticationFailure>;
 }
 
 /// Makes authorisation decisions regarding oper actions
@@ -16,9 +46,19 @@ pub trait OperPolicyService
 
     /// Utility function to determine whether the given user is opered (regardless of privileges)
     fn require_oper(&self, user: &wrapper::User) -> PermissionResult;
+    /// Utility function to determine whether the given user is opered with the given privilege
+    fn require_privilege(&self, user: &wrapper::User, privilege: state::OperPrivilege) -> PermissionResult;
 
     /// Determine whether the given oper can set a kline
     fn can_set_kline(&self, oper: &wrapper::User, user: &Pattern, host: &Pattern, duration: i64) -> PermissionResult;
+    /// Determine whether the given oper can remove a kline
+    fn can_remove_kline(&self, oper: &wrapper::User) -> PermissionResult;
     /// Determine whether the given oper can disconnect the given target user
     fn can_kill(&self, oper: &wrapper::User, target: &wrapper::User) -> PermissionResult;
+    /// Determine whether the given oper can change the username and hostname of the given target user
+    fn can_change_host(&self, oper: &wrapper::User, target: &wrapper::User) -> PermissionResult;
+    /// Determine whether the given oper can see secret channels and their members
+    fn can_see_secret(&self, oper: &wrapper::User) -> PermissionResult;
+    /// Determine whether the given oper can perform administrative actions on services data
+    fn can_administer_services(&self, oper: &wrapper::User) -> PermissionResult;
 }
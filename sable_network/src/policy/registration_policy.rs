@@ -21,4 +21,7 @@ pub trait RegistrationPolicyService
 
     /// Determine whether the given user can create/edit a role with the given flags
     fn can_create_role(&self, source: &wrapper::Account, channel: &wrapper::ChannelRegistration, with_flags: &state::ChannelAccessSet) -> PermissionResult;
+
+    /// Determine whether the given user can change the persistent settings of a channel
+    fn can_change_settings(&self, source: &wrapper::Account, channel: &wrapper::ChannelRegistration) -> PermissionResult;
 }
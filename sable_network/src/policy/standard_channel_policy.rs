@@ -179,6 +179,25 @@ impl ChannelPolicyService for StandardChannelPolicy
         has_access(user, channel, required_permission)
     }
 
+    fn auto_permissions(&self, user: &User, channel: &Channel) -> MembershipFlagSet
+    {
+        let mut permissions = MembershipFlagSet::new();
+
+        for flags in [has_assigned_access(user, channel), has_default_access(channel)].iter().flatten()
+        {
+            if flags.is_set(ChannelAccessFlag::OpAuto)
+            {
+                permissions |= MembershipFlagFlag::Op;
+            }
+            if flags.is_set(ChannelAccessFlag::VoiceAuto)
+            {
+                permissions |= MembershipFlagFlag::Voice;
+            }
+        }
+
+        permissions
+    }
+
     fn validate_ban_mask(&self, _mask: &str, _mode_type: ListModeType, _channel: &Channel) -> PermissionResult
     {
         Ok(())
@@ -242,4 +261,20 @@ impl ChannelPolicyService for StandardChannelPolicy
     {
         has_access(user, channel, ChannelAccessFlag::InviteOther)
     }
+
+    fn can_kick(&self, user: &User, channel: &Channel, _target: &User) -> PermissionResult
+    {
+        has_access(user, channel, ChannelAccessFlag::Kick)
+    }
+
+    fn can_rename(&self, user: &User, channel: &Channel, _new_name: &ChannelName) -> PermissionResult
+    {
+        // Registrations are looked up by name, so renaming would detach a channel from its registration
+        if channel.is_registered().is_some()
+        {
+            return Err(PermissionError::Channel(*channel.name(), NoAccess));
+        }
+
+        has_access(user, channel, ChannelAccessFlag::SetSimpleMode)
+    }
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
 use super::*;
 use crate::network::config::OperConfig;
+use state::OperPrivilege;
 
 use UserPermissionError::*;
 
@@ -38,21 +39,182 @@ impl OperPolicyService for StandardOperPolicy
         }
     }
 
+    fn require_privilege(&self, user: &wrapper::User, privilege: OperPrivilege) -> PermissionResult
+    {
+        match user.oper_privileges()
+        {
+            Some(privs) if privs.privileges.has(privilege) => Ok(()),
+            Some(_) => Err(PermissionError::User(MissingPrivilege(privilege))),
+            None => Err(PermissionError::User(NotOper))
+        }
+    }
+
     fn can_set_kline(&self, oper: &wrapper::User, _user: &Pattern, _host: &Pattern, _duration: i64) -> PermissionResult
     {
-        self.require_oper(oper)
+        self.require_privilege(oper, OperPrivilege::Kline)
+    }
+
+    fn can_remove_kline(&self, oper: &wrapper::User) -> PermissionResult
+    {
+        self.require_privilege(oper, OperPrivilege::Kline)
     }
 
     fn can_kill(&self, oper: &wrapper::User, _target: &wrapper::User) -> PermissionResult
     {
-        self.require_oper(oper)
+        self.require_privilege(oper, OperPrivilege::Kill)
+    }
+
+    fn can_change_host(&self, oper: &wrapper::User, _target: &wrapper::User) -> PermissionResult
+    {
+        self.require_privilege(oper, OperPrivilege::Chghost)
+    }
+
+    fn can_see_secret(&self, oper: &wrapper::User) -> PermissionResult
+    {
+        self.require_privilege(oper, OperPrivilege::SeeSecret)
+    }
+
+    fn can_administer_services(&self, oper: &wrapper::User) -> PermissionResult
+    {
+        self.require_privilege(oper, OperPrivilege::ServicesAdmin)
     }
 }
 
 impl OperAuthenticationService for StandardOperPolicy
 {
-    fn authenticate(&self, oper_config: &OperConfig, user: &str, pass: &str) -> bool
+    fn authenticate(&self, oper_config: &OperConfig, user: &wrapper::User,
+                    oper_name: &str, pass: &str, fingerprint: Option<&str>) -> Result<(), OperAuthenticationFailure>
+    {
+        if oper_name != oper_config.name || !unix::verify(pass, &oper_config.hash)
+        {
+            return Err(OperAuthenticationFailure::BadPassword);
+        }
+
+        if let Some(required) = &oper_config.fingerprint
+        {
+            if !fingerprint.map(|fp| fp.eq_ignore_ascii_case(required)).unwrap_or(false)
+            {
+                return Err(OperAuthenticationFailure::FingerprintMismatch);
+            }
+        }
+
+        if let Some(required) = &oper_config.account
+        {
+            // Fail closed if the account can't be looked up
+            let account_name = user.account().ok().flatten().map(|a| a.name());
+            if account_name.as_ref() != Some(required)
+            {
+                return Err(OperAuthenticationFailure::AccountMismatch);
+            }
+        }
+
+        Ok(())
+    }
+}
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::network::tests::fixtures::NetworkBuilder;
+    use crate::network::event::details;
+    use std::str::FromStr;
+
+    const FINGERPRINT: &str = "0123456789abcdef";
+
+    fn oper_config(fingerprint: Option<&str>, account: Option<&str>) -> OperConfig
+    {
+        OperConfig {
+            name: "oper".to_string(),
+            hash: unix::crypt("password", "$6$saltsalt").unwrap(),
+            class: Some("admin".to_string()),
+            fingerprint: fingerprint.map(ToOwned::to_owned),
+            account: account.map(|a| Nickname::from_str(a).unwrap()),
+        }
+    }
+
+    /// Build a network with a single user, optionally logged in to the named account
+    fn network_with_user(account: Option<&str>) -> (NetworkBuilder, UserId)
+    {
+        let mut builder = NetworkBuilder::new();
+        let nick = Nickname::from_str("aaa").unwrap();
+        builder.add_user(nick);
+        let user_id = builder.net.user_by_nick(&nick).unwrap().id();
+
+        if let Some(account) = account
+        {
+            builder.log_in(user_id, Nickname::from_str(account).unwrap());
+        }
+
+        (builder, user_id)
+    }
+
+    fn authenticate(conf: &OperConfig, account: Option<&str>, fingerprint: Option<&str>) -> Result<(), OperAuthenticationFailure>
     {
-        user == oper_config.name && unix::verify(pass, &oper_config.hash)
+        let (builder, user_id) = network_with_user(account);
+        let user = builder.net.user(user_id).unwrap();
+
+        StandardOperPolicy::new().authenticate(conf, &user, "oper", "password", fingerprint)
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn privileges_are_checked_individually()
+    {
+        let (mut builder, user_id) = network_with_user(None);
+        let policy = StandardOperPolicy::new();
+
+        let user = builder.net.user(user_id).unwrap();
+        assert!(matches!(policy.can_kill(&user, &user), Err(PermissionError::User(NotOper))));
+
+        builder.apply(user_id, details::OperUp {
+            oper_name: "oper".to_string(),
+            privileges: vec![OperPrivilege::Kill].into()
+        });
+
+        let user = builder.net.user(user_id).unwrap();
+        assert!(policy.can_kill(&user, &user).is_ok());
+        assert!(matches!(policy.can_change_host(&user, &user), Err(PermissionError::User(MissingPrivilege(OperPrivilege::Chghost)))));
+        assert!(matches!(policy.can_remove_kline(&user), Err(PermissionError::User(MissingPrivilege(OperPrivilege::Kline)))));
+    }
+
+    #[test]
+    fn fingerprint_required_but_missing()
+    {
+        let conf = oper_config(Some(FINGERPRINT), None);
+        assert_eq!(authenticate(&conf, None, None), Err(OperAuthenticationFailure::FingerprintMismatch));
+    }
+
+    #[test]
+    fn fingerprint_mismatch()
+    {
+        let conf = oper_config(Some(FINGERPRINT), None);
+        assert_eq!(authenticate(&conf, None, Some("fedcba9876543210")), Err(OperAuthenticationFailure::FingerprintMismatch));
+    }
+
+    #[test]
+    fn fingerprint_matches_ignoring_case()
+    {
+        let conf = oper_config(Some(FINGERPRINT), None);
+        assert_eq!(authenticate(&conf, None, Some(&FINGERPRINT.to_ascii_uppercase())), Ok(()));
+    }
+
+    #[test]
+    fn account_required_while_logged_out()
+    {
+        let conf = oper_config(None, Some("opaccount"));
+        assert_eq!(authenticate(&conf, None, None), Err(OperAuthenticationFailure::AccountMismatch));
+    }
+
+    #[test]
+    fn account_mismatch()
+    {
+        let conf = oper_config(None, Some("opaccount"));
+        assert_eq!(authenticate(&conf, Some("otheraccount"), None), Err(OperAuthenticationFailure::AccountMismatch));
+    }
+
+    #[test]
+    fn all_requirements_met()
+    {
+        let conf = oper_config(Some(FINGERPRINT), Some("opaccount"));
+        assert_eq!(authenticate(&conf, Some("opaccount"), Some(FINGERPRINT)), Ok(()));
+    }
+}
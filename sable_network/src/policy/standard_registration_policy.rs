@@ -124,4 +124,17 @@ impl RegistrationPolicyService for StandardRegistrationPolicy
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    fn can_change_settings(&self, source: &wrapper::Account, channel: &wrapper::ChannelRegistration) -> PermissionResult
+    {
+        let source_access = source.has_access_in(channel.id())
+                                  .ok_or(RegistrationPermissionError::NoAccess)?;
+
+        if ! source_access.role()?.flags().is_set(ChannelAccessFlag::Founder)
+        {
+            return Err(RegistrationPermissionError::NoAccess.into());
+        }
+
+        Ok(())
+    }
+}
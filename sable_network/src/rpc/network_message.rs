@@ -2,7 +2,11 @@ use crate::{
     network::{
         event::*,
         Network,
-        state::ChannelAccessSet,
+        state::{
+            ChannelAccessSet,
+            ChannelModeLock,
+            PersistentListEntry,
+        },
     },
     id::*,
     validated::*,
@@ -49,17 +53,37 @@ pub enum RemoteServerRequestType
     /// Simple ping for communication tests
     Ping,
     /// User attempting registration
-    /// Parameters: account name being registered, password provided
-    RegisterUser(Nickname, String),
+    /// Parameters: account name being registered, password provided, email address (if any)
+    RegisterUser(Nickname, String, Option<String>),
+    /// Complete a registration which is waiting for email verification
+    /// Parameters: account name being registered, verification code
+    VerifyAccount(Nickname, String),
     /// User attempting login
     /// Parameters: account id, password
     UserLogin(AccountId, String),
+    /// Change an account's password
+    /// Parameters: account id, new password
+    SetPassword(AccountId, String),
+    /// Generate a password recovery token for an account, replacing any existing one
+    BeginPasswordRecovery(AccountId),
+    /// Set a new password using a recovery token
+    /// Parameters: account id, recovery token, new password
+    RecoverPassword(AccountId, String, String),
+    /// Delete an account, along with its nick registrations, channel accesses and any
+    /// channel registrations for which it is the only founder
+    /// Parameters: account id, password
+    DropAccount(AccountId, String),
     /// Begin SASL auth
-    BeginAuthenticate(SaslSessionId, String),
+    /// Parameters: session id, mechanism name, client certificate fingerprint (if any)
+    BeginAuthenticate(SaslSessionId, String, Option<String>),
     /// SASL traffic
     Authenticate(SaslSessionId, Vec<u8>),
     /// Abort a SASL session
     AbortAuthenticate(SaslSessionId),
+    /// Register a nickname to an account
+    GroupNick(AccountId, Nickname),
+    /// Remove a nickname registration from an account
+    UngroupNick(AccountId, Nickname),
     /// Register a channel
     RegisterChannel(AccountId, ChannelId),
     /// Add, modify or remove a channel access (None to delete)
@@ -68,12 +92,25 @@ pub enum RemoteServerRequestType
     CreateRole{ source: AccountId, channel: ChannelRegistrationId, name: CustomRoleName, flags: ChannelAccessSet },
     /// Modify or delete a channel role
     ModifyRole{ source: AccountId, id: ChannelRoleId, flags: Option<ChannelAccessSet> },
+    /// Change one of the persistent settings stored in a channel registration
+    ModifyChannelSettings{ source: AccountId, channel: ChannelRegistrationId, change: ChannelSettingChange },
     /// Add an authorised fingerprint to an account
     AddAccountFingerprint(AccountId, String),
     /// Remove an authorised fingerprint from an account
     RemoveAccountFingerprint(AccountId, String),
 }
 
+/// A change to the persistent settings of a registered channel. `None` or an empty
+/// list clears the corresponding setting.
+#[derive(Debug,Clone,serde::Serialize,serde::Deserialize)]
+pub enum ChannelSettingChange
+{
+    TopicLock(Option<String>),
+    ModeLock(Option<ChannelModeLock>),
+    EntryMessage(Option<String>),
+    ListEntries(Vec<PersistentListEntry>),
+}
+
 /// A SASL authentication response
 #[derive(Debug,Clone,serde::Serialize,serde::Deserialize)]
 pub enum AuthenticateStatus
@@ -100,10 +137,16 @@ pub enum RemoteServerResponse
     LogUserIn(AccountId),
     /// SASL response
     Authenticate(AuthenticateStatus),
+    /// A password recovery token has been generated
+    RecoveryToken(String),
     /// Operation failed due to invalid credentials
     InvalidCredentials,
     /// Registration failed because the account exists
     AlreadyExists,
+    /// Registration will be completed once the user verifies their email address
+    VerificationRequired,
+    /// Registration failed because the email address is missing or invalid
+    InvalidEmail,
     /// Operation failed because of insufficient privileges
     AccessDenied,
     /// User isn't registered or account doesn't exist
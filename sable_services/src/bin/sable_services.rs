@@ -27,7 +27,7 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>>
 {
     let opts = Opts::from_args();
 
-    sable_server::run::run_server::<sable_services::ServicesServer<sable_services::database::jsonfile::JsonDatabase>>(
+    sable_server::run::run_server::<sable_services::ServicesServer<sable_services::database::any::AnyDatabase>>(
                             opts.server_conf,
                             opts.network_conf,
                             opts.foreground,
@@ -0,0 +1,38 @@
+use structopt::StructOpt;
+use sable_services::database::{
+    DatabaseConnection,
+    any::AnyDatabase,
+    migrate::migrate,
+};
+
+/// Copy all services data from one database to another, for example to move from a
+/// JSON file to SQLite. Database connection strings are in the same form as the
+/// `database` setting in the services config.
+#[derive(Debug,StructOpt)]
+#[structopt(rename_all = "kebab")]
+struct Opts
+{
+    /// Connection string for the database to read from
+    #[structopt(long)]
+    from: String,
+
+    /// Connection string for the database to write to. This should be empty.
+    #[structopt(long)]
+    to: String,
+}
+
+pub fn main() -> Result<(), Box<dyn std::error::Error>>
+{
+    let opts = Opts::from_args();
+
+    let source = AnyDatabase::connect(&opts.from)?;
+    let target = AnyDatabase::connect(&opts.to)?;
+
+    let summary = migrate(&source, &target)?;
+
+    println!("Migrated {} accounts, {} nick registrations, {} channel registrations, {} channel roles and {} channel accesses",
+             summary.accounts, summary.nick_registrations, summary.channel_registrations,
+             summary.channel_roles, summary.channel_accesses);
+
+    Ok(())
+}
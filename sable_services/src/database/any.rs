@@ -0,0 +1,203 @@
+use super::*;
+
+/// A database whose backend is chosen at runtime from the connection string
+///
+/// The connection string takes the form `<backend>://<path>`, where backend is one of:
+///  - `json`: [`JsonDatabase`](jsonfile::JsonDatabase)
+///  - `sqlite`: [`SqliteDatabase`](sqlite::SqliteDatabase)
+///
+/// A connection string with no scheme is treated as the path to a JSON database file.
+pub enum AnyDatabase
+{
+    Json(Box<jsonfile::JsonDatabase>),
+    Sqlite(sqlite::SqliteDatabase),
+}
+
+macro_rules! dispatch {
+    ($self:ident . $method:ident ( $($arg:expr),* )) => {
+        match $self
+        {
+            AnyDatabase::Json(db) => db.$method($($arg),*),
+            AnyDatabase::Sqlite(db) => db.$method($($arg),*),
+        }
+    }
+}
+
+macro_rules! dispatch_iter {
+    ($self:ident . $method:ident ()) => {
+        Ok(match $self
+        {
+            AnyDatabase::Json(db) => Box::new(db.$method()?) as Box<dyn Iterator<Item=_>>,
+            AnyDatabase::Sqlite(db) => Box::new(db.$method()?),
+        })
+    }
+}
+
+impl DatabaseConnection for AnyDatabase
+{
+    fn connect(conn: impl AsRef<str>) -> Result<Self>
+    {
+        match conn.as_ref().split_once("://")
+        {
+            Some(("json", path)) => Ok(Self::Json(Box::new(jsonfile::JsonDatabase::connect(path)?))),
+            Some(("sqlite", path)) => Ok(Self::Sqlite(sqlite::SqliteDatabase::connect(path)?)),
+            Some((scheme, _)) => Err(DatabaseError::DbError(format!("Unknown database type {}", scheme).into())),
+            None => Ok(Self::Json(Box::new(jsonfile::JsonDatabase::connect(conn)?))),
+        }
+    }
+
+    fn new_account(&self, data: state::Account, auth: AccountAuth) -> Result<state::Account>
+    {
+        dispatch!(self.new_account(data, auth))
+    }
+
+    fn account(&self, id: AccountId) -> Result<state::Account>
+    {
+        dispatch!(self.account(id))
+    }
+
+    fn account_named(&self, name: &Nickname) -> Result<state::Account>
+    {
+        dispatch!(self.account_named(name))
+    }
+
+    fn update_account(&self, new_data: &state::Account) -> Result<()>
+    {
+        dispatch!(self.update_account(new_data))
+    }
+
+    fn all_accounts(&self) -> Result<impl Iterator<Item=state::Account> + '_>
+    {
+        dispatch_iter!(self.all_accounts())
+    }
+
+    fn remove_account(&self, id: AccountId) -> Result<()>
+    {
+        dispatch!(self.remove_account(id))
+    }
+
+    fn auth_for_account(&self, id: AccountId) -> Result<AccountAuth>
+    {
+        dispatch!(self.auth_for_account(id))
+    }
+
+    fn update_auth(&self, new_data: &AccountAuth) -> Result<()>
+    {
+        dispatch!(self.update_auth(new_data))
+    }
+
+    fn new_pending_registration(&self, data: PendingRegistration) -> Result<PendingRegistration>
+    {
+        dispatch!(self.new_pending_registration(data))
+    }
+
+    fn pending_registration(&self, name: &Nickname) -> Result<PendingRegistration>
+    {
+        dispatch!(self.pending_registration(name))
+    }
+
+    fn all_pending_registrations(&self) -> Result<impl Iterator<Item=PendingRegistration> + '_>
+    {
+        dispatch_iter!(self.all_pending_registrations())
+    }
+
+    fn remove_pending_registration(&self, name: &Nickname) -> Result<()>
+    {
+        dispatch!(self.remove_pending_registration(name))
+    }
+
+    fn new_nick_registration(&self, data: state::NickRegistration) -> Result<state::NickRegistration>
+    {
+        dispatch!(self.new_nick_registration(data))
+    }
+
+    fn nick_registration(&self, id: NickRegistrationId) -> Result<state::NickRegistration>
+    {
+        dispatch!(self.nick_registration(id))
+    }
+
+    fn update_nick_registration(&self, new_data: &state::NickRegistration) -> Result<()>
+    {
+        dispatch!(self.update_nick_registration(new_data))
+    }
+
+    fn all_nick_registrations(&self) -> Result<impl Iterator<Item=state::NickRegistration> + '_>
+    {
+        dispatch_iter!(self.all_nick_registrations())
+    }
+
+    fn remove_nick_registration(&self, id: NickRegistrationId) -> Result<()>
+    {
+        dispatch!(self.remove_nick_registration(id))
+    }
+
+    fn new_channel_registration(&self, data: state::ChannelRegistration) -> Result<state::ChannelRegistration>
+    {
+        dispatch!(self.new_channel_registration(data))
+    }
+
+    fn channel_registration(&self, id: ChannelRegistrationId) -> Result<state::ChannelRegistration>
+    {
+        dispatch!(self.channel_registration(id))
+    }
+
+    fn update_channel_registration(&self, new_data: &state::ChannelRegistration) -> Result<()>
+    {
+        dispatch!(self.update_channel_registration(new_data))
+    }
+
+    fn all_channel_registrations(&self) -> Result<impl Iterator<Item=state::ChannelRegistration> + '_>
+    {
+        dispatch_iter!(self.all_channel_registrations())
+    }
+
+    fn remove_channel_registration(&self, id: ChannelRegistrationId) -> Result<()>
+    {
+        dispatch!(self.remove_channel_registration(id))
+    }
+
+    fn new_channel_role(&self, data: state::ChannelRole) -> Result<state::ChannelRole>
+    {
+        dispatch!(self.new_channel_role(data))
+    }
+
+    fn channel_role(&self, id: ChannelRoleId) -> Result<state::ChannelRole>
+    {
+        dispatch!(self.channel_role(id))
+    }
+
+    fn update_channel_role(&self, data: &state::ChannelRole) -> Result<()>
+    {
+        dispatch!(self.update_channel_role(data))
+    }
+
+    fn all_channel_roles(&self) -> Result<impl Iterator<Item=state::ChannelRole> + '_>
+    {
+        dispatch_iter!(self.all_channel_roles())
+    }
+
+    fn remove_channel_role(&self, id: ChannelRoleId) -> Result<()>
+    {
+        dispatch!(self.remove_channel_role(id))
+    }
+
+    fn update_channel_access(&self, data: &state::ChannelAccess) -> Result<()>
+    {
+        dispatch!(self.update_channel_access(data))
+    }
+
+    fn channel_access(&self, id: ChannelAccessId) -> Result<state::ChannelAccess>
+    {
+        dispatch!(self.channel_access(id))
+    }
+
+    fn all_channel_accesses(&self) -> Result<impl Iterator<Item=state::ChannelAccess> + '_>
+    {
+        dispatch_iter!(self.all_channel_accesses())
+    }
+
+    fn remove_channel_access(&self, id: ChannelAccessId) -> Result<()>
+    {
+        dispatch!(self.remove_channel_access(id))
+    }
+}
@@ -30,6 +30,10 @@ struct JsonDatabaseState
     #[serde_as(as = "Vec<(_,_)>")]
     account_auth: HashMap<AccountId, AccountAuth>,
 
+    #[serde_as(as = "Vec<(_,_)>")]
+    #[serde(default)]
+    pending_registrations: HashMap<Nickname, PendingRegistration>,
+
     #[serde_as(as = "Vec<(_,_)>")]
     nick_registrations: HashMap<NickRegistrationId, state::NickRegistration>,
 
@@ -161,11 +165,64 @@ impl DatabaseConnection for JsonDatabase
         Ok(LockedHashMapValueIterator::new(self.state.read(), |state| state.accounts.values()))
     }
 
+    fn remove_account(&self, id: AccountId) -> Result<()>
+    {
+        let mut state = self.state.write();
+        state.accounts.remove(&id);
+        state.account_auth.remove(&id);
+        drop(state);
+
+        self.save()
+    }
+
     fn auth_for_account(&self, id: AccountId) -> Result<AccountAuth>
     {
         self.state.read().account_auth.get(&id).ok_or(DatabaseError::NoSuchId).cloned()
     }
 
+    fn update_auth(&self, new_data: &AccountAuth) -> Result<()>
+    {
+        let ret = match self.state.write().account_auth.entry(new_data.account)
+        {
+            Entry::Occupied(mut entry) => {
+                entry.insert(new_data.clone());
+                Ok(())
+            }
+            Entry::Vacant(_) => Err(DatabaseError::NoSuchId)
+        };
+
+        self.save()?;
+        ret
+    }
+
+    fn new_pending_registration(&self, data: PendingRegistration) -> Result<PendingRegistration>
+    {
+        let ret = match self.state.write().pending_registrations.entry(data.name)
+        {
+            Entry::Occupied(_) => Err(DatabaseError::DuplicateName),
+            Entry::Vacant(entry) => Ok(entry.insert(data).clone())
+        };
+
+        self.save()?;
+        ret
+    }
+
+    fn pending_registration(&self, name: &Nickname) -> Result<PendingRegistration>
+    {
+        self.state.read().pending_registrations.get(name).ok_or(DatabaseError::NoSuchId).cloned()
+    }
+
+    fn all_pending_registrations(&self) -> Result<impl Iterator<Item=PendingRegistration> + '_>
+    {
+        Ok(LockedHashMapValueIterator::new(self.state.read(), |state| state.pending_registrations.values()))
+    }
+
+    fn remove_pending_registration(&self, name: &Nickname) -> Result<()>
+    {
+        self.state.write().pending_registrations.remove(name);
+        self.save()
+    }
+
     fn new_nick_registration(&self, data: state::NickRegistration) -> Result<state::NickRegistration>
     {
         let ret = match self.state.write().nick_registrations.entry(data.id)
@@ -203,6 +260,12 @@ impl DatabaseConnection for JsonDatabase
         Ok(LockedHashMapValueIterator::new(self.state.read(), |state| state.nick_registrations.values()))
     }
 
+    fn remove_nick_registration(&self, id: NickRegistrationId) -> Result<()>
+    {
+        self.state.write().nick_registrations.remove(&id);
+        self.save()
+    }
+
     fn new_channel_registration(&self, data: state::ChannelRegistration) -> Result<state::ChannelRegistration>
     {
         let mut state = self.state.write();
@@ -246,6 +309,12 @@ impl DatabaseConnection for JsonDatabase
         Ok(LockedHashMapValueIterator::new(self.state.read(), |state| state.channel_registrations.values()))
     }
 
+    fn remove_channel_registration(&self, id: ChannelRegistrationId) -> Result<()>
+    {
+        self.state.write().channel_registrations.remove(&id);
+        self.save()
+    }
+
     fn channel_access(&self, id: ChannelAccessId) -> Result<state::ChannelAccess>
     {
         self.state.read().channel_accesses.get(&id).ok_or(DatabaseError::NoSuchId).cloned()
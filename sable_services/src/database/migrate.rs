@@ -0,0 +1,221 @@
+//! Copy the contents of one database backend into another
+
+use super::*;
+
+#[derive(Debug,Error)]
+pub enum MigrationError
+{
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+    #[error("{0} didn't round-trip correctly")]
+    Mismatch(String),
+}
+
+/// Counts of the objects copied by [`migrate`]
+#[derive(Debug,Default,PartialEq)]
+pub struct MigrationSummary
+{
+    pub accounts: usize,
+    pub pending_registrations: usize,
+    pub nick_registrations: usize,
+    pub channel_registrations: usize,
+    pub channel_roles: usize,
+    pub channel_accesses: usize,
+}
+
+fn check(matches: bool, what: impl FnOnce() -> String) -> std::result::Result<(), MigrationError>
+{
+    if matches { Ok(()) } else { Err(MigrationError::Mismatch(what())) }
+}
+
+/// Copy every object in `source` into `target`, then read each one back from `target`,
+/// by ID and (for accounts) by name, to check that it was stored intact.
+///
+/// `target` is expected to be empty; existing objects with the same IDs or names will
+/// cause the migration to fail.
+pub fn migrate(source: &impl DatabaseConnection, target: &impl DatabaseConnection) -> std::result::Result<MigrationSummary, MigrationError>
+{
+    let mut summary = MigrationSummary::default();
+
+    for account in source.all_accounts()?
+    {
+        let auth = source.auth_for_account(account.id)?;
+        target.new_account(account, auth)?;
+        summary.accounts += 1;
+    }
+    for pending in source.all_pending_registrations()?
+    {
+        target.new_pending_registration(pending)?;
+        summary.pending_registrations += 1;
+    }
+    for nick_registration in source.all_nick_registrations()?
+    {
+        target.new_nick_registration(nick_registration)?;
+        summary.nick_registrations += 1;
+    }
+    for channel_registration in source.all_channel_registrations()?
+    {
+        target.new_channel_registration(channel_registration)?;
+        summary.channel_registrations += 1;
+    }
+    for role in source.all_channel_roles()?
+    {
+        target.new_channel_role(role)?;
+        summary.channel_roles += 1;
+    }
+    for access in source.all_channel_accesses()?
+    {
+        target.update_channel_access(&access)?;
+        summary.channel_accesses += 1;
+    }
+
+    verify(source, target)?;
+
+    Ok(summary)
+}
+
+fn verify(source: &impl DatabaseConnection, target: &impl DatabaseConnection) -> std::result::Result<(), MigrationError>
+{
+    for account in source.all_accounts()?
+    {
+        check(target.account(account.id)? == account, || format!("Account {:?}", account.id))?;
+        check(target.account_named(&account.name)?.id == account.id, || format!("Account name {}", account.name))?;
+
+        check(target.auth_for_account(account.id)? == source.auth_for_account(account.id)?,
+              || format!("Authentication data for account {}", account.name))?;
+    }
+    for pending in source.all_pending_registrations()?
+    {
+        check(target.pending_registration(&pending.name)? == pending,
+              || format!("Pending registration {}", pending.name))?;
+    }
+    for nick_registration in source.all_nick_registrations()?
+    {
+        check(target.nick_registration(nick_registration.id)? == nick_registration,
+              || format!("Nick registration {}", nick_registration.nick))?;
+    }
+    for channel_registration in source.all_channel_registrations()?
+    {
+        check(target.channel_registration(channel_registration.id)? == channel_registration,
+              || format!("Channel registration {}", channel_registration.channelname))?;
+    }
+    for role in source.all_channel_roles()?
+    {
+        check(target.channel_role(role.id)? == role, || format!("Channel role {:?}", role.id))?;
+    }
+    for access in source.all_channel_accesses()?
+    {
+        check(target.channel_access(access.id)? == access, || format!("Channel access {:?}", access.id))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use std::str::FromStr;
+    use jsonfile::JsonDatabase;
+    use sqlite::SqliteDatabase;
+
+    /// Fill a database with one of each kind of object, including every optional
+    /// authentication field, and return the account's name and authentication data
+    fn populate(source: &impl DatabaseConnection) -> (Nickname, AccountAuth)
+    {
+        let ids = ObjectIdGenerator::new(ServerId::new(1), EpochId::new(1));
+
+        let account = state::Account { id: ids.next_account(), name: Nickname::from_str("alice").unwrap(), authorised_fingerprints: Vec::new() };
+        let auth = AccountAuth {
+            account: account.id,
+            password_hash: "hash".to_owned(),
+            scram_sha256: Some(ScramCredentials {
+                salt: vec![1, 2, 3],
+                iterations: 4096,
+                stored_key: vec![4, 5, 6],
+                server_key: vec![7, 8, 9],
+            }),
+            recovery_token: Some(RecoveryToken { token_hash: "token".to_owned(), expires: 1000 }),
+            email: Some("alice@example.com".to_owned()),
+        };
+        source.new_account(account.clone(), auth.clone()).unwrap();
+
+        let pending = PendingRegistration {
+            name: Nickname::from_str("bob").unwrap(),
+            auth: AccountAuth { account: ids.next_account(), password_hash: "hash2".to_owned(), scram_sha256: None, recovery_token: None, email: Some("bob@example.com".to_owned()) },
+            code_hash: "code".to_owned(),
+            expires: 2000,
+        };
+        source.new_pending_registration(pending).unwrap();
+
+        let nick = state::NickRegistration { id: ids.next_nick_registration(), nick: account.name, account: account.id };
+        source.new_nick_registration(nick).unwrap();
+
+        let channel = state::ChannelRegistration {
+            id: ids.next_channel_registration(),
+            channelname: ChannelName::from_str("#test").unwrap(),
+            topic_lock: Some("topic".to_owned()),
+            mode_lock: None,
+            entry_message: None,
+            list_entries: Vec::new(),
+        };
+        source.new_channel_registration(channel.clone()).unwrap();
+
+        let role = state::ChannelRole {
+            id: ids.next_channel_role(),
+            channel: Some(channel.id),
+            name: state::ChannelRoleName::BuiltinOp,
+            flags: state::ChannelAccessSet::new()
+        };
+        source.new_channel_role(role.clone()).unwrap();
+
+        let access = state::ChannelAccess { id: ChannelAccessId::new(account.id, channel.id), role: role.id };
+        source.update_channel_access(&access).unwrap();
+
+        (account.name, auth)
+    }
+
+    fn check_migrated(source: &impl DatabaseConnection, target: &impl DatabaseConnection, account_name: &Nickname, auth: &AccountAuth)
+    {
+        let summary = migrate(source, target).unwrap();
+
+        assert_eq!(summary, MigrationSummary {
+            accounts: 1,
+            pending_registrations: 1,
+            nick_registrations: 1,
+            channel_registrations: 1,
+            channel_roles: 1,
+            channel_accesses: 1,
+        });
+
+        let account = target.account_named(account_name).unwrap();
+        assert!(target.auth_for_account(account.id).unwrap() == *auth);
+    }
+
+    #[test]
+    fn migrate_sqlite_to_sqlite()
+    {
+        let source = SqliteDatabase::connect(":memory:").unwrap();
+        let (account_name, auth) = populate(&source);
+
+        let target = SqliteDatabase::connect(":memory:").unwrap();
+        check_migrated(&source, &target, &account_name, &auth);
+    }
+
+    #[test]
+    fn migrate_json_to_sqlite()
+    {
+        let filename = std::env::temp_dir().join(format!("sable-migrate-test-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&filename);
+
+        let source = JsonDatabase::connect(filename.to_str().unwrap()).unwrap();
+        let (account_name, auth) = populate(&source);
+
+        // Reload from disk, so that the file format is covered as well
+        let source = JsonDatabase::connect(filename.to_str().unwrap()).unwrap();
+        let target = SqliteDatabase::connect(":memory:").unwrap();
+        check_migrated(&source, &target, &account_name, &auth);
+
+        std::fs::remove_file(&filename).unwrap();
+    }
+}
@@ -36,7 +36,7 @@ pub type Result<T> = std::result::Result<T, DatabaseError>;
 pub trait DatabaseConnection : Sized + Send + Sync + 'static
 {
     /// Constructor. The format of `conn` is defined by the provider and taken from the
-    /// server config file.
+    /// server config file; see [`any::AnyDatabase`] for how the backend is selected.
     fn connect(conn: impl AsRef<str>) -> Result<Self>;
 
     /// Create a new account, store it in the database, and return it
@@ -49,9 +49,22 @@ pub trait DatabaseConnection : Sized + Send + Sync + 'static
     fn update_account(&self, new_data: &state::Account) -> Result<()>;
     /// Retrieve all accounts in the database
     fn all_accounts(&self) -> Result<impl Iterator<Item=state::Account> + '_>;
+    /// Remove an account and its authentication data
+    fn remove_account(&self, id: AccountId) -> Result<()>;
 
     /// Retrieve the authentication data for a given account
     fn auth_for_account(&self, id: AccountId) -> Result<AccountAuth>;
+    /// Update the authentication data for an account
+    fn update_auth(&self, new_data: &AccountAuth) -> Result<()>;
+
+    /// Store an account registration which is awaiting email verification
+    fn new_pending_registration(&self, data: PendingRegistration) -> Result<PendingRegistration>;
+    /// Retrieve the pending registration for a given account name
+    fn pending_registration(&self, name: &Nickname) -> Result<PendingRegistration>;
+    /// Retrieve all pending registrations in the database
+    fn all_pending_registrations(&self) -> Result<impl Iterator<Item=PendingRegistration> + '_>;
+    /// Remove a pending registration
+    fn remove_pending_registration(&self, name: &Nickname) -> Result<()>;
 
     /// Create a new nick registration, store it in the database, and return it
     fn new_nick_registration(&self, data: state::NickRegistration) -> Result<state::NickRegistration>;
@@ -61,6 +74,8 @@ pub trait DatabaseConnection : Sized + Send + Sync + 'static
     fn update_nick_registration(&self, new_data: &state::NickRegistration) -> Result<()>;
     /// Retrieve all nick registrations in the database
     fn all_nick_registrations(&self) -> Result<impl Iterator<Item=state::NickRegistration> + '_>;
+    /// Remove a nick registration
+    fn remove_nick_registration(&self, id: NickRegistrationId) -> Result<()>;
 
     /// Create a new channel registration, store it in the database, and return it
     fn new_channel_registration(&self, data: state::ChannelRegistration) -> Result<state::ChannelRegistration>;
@@ -70,6 +85,8 @@ pub trait DatabaseConnection : Sized + Send + Sync + 'static
     fn update_channel_registration(&self, new_data: &state::ChannelRegistration) -> Result<()>;
     /// Retrieve all channel registrations in the database
     fn all_channel_registrations(&self) -> Result<impl Iterator<Item=state::ChannelRegistration> + '_>;
+    /// Remove a channel registration
+    fn remove_channel_registration(&self, id: ChannelRegistrationId) -> Result<()>;
 
     /// Create a new channel role
     fn new_channel_role(&self, data: state::ChannelRole) -> Result<state::ChannelRole>;
@@ -94,4 +111,7 @@ pub trait DatabaseConnection : Sized + Send + Sync + 'static
     fn remove_channel_access(&self, id: ChannelAccessId) -> Result<()>;
 }
 
-pub mod jsonfile;
\ No newline at end of file
+pub mod jsonfile;
+pub mod sqlite;
+pub mod any;
+pub mod migrate;
\ No newline at end of file
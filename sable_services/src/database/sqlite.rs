@@ -0,0 +1,432 @@
+use std::path::Path;
+use parking_lot::Mutex;
+use rusqlite::{Connection, OptionalExtension, Transaction, params, ErrorCode};
+use serde::{Serialize, de::DeserializeOwned};
+
+use super::*;
+
+/// An SQLite-backed database
+///
+/// Each object is stored as a JSON document alongside the key and name columns used
+/// to look it up and to enforce uniqueness. Every write happens inside a transaction.
+pub struct SqliteDatabase
+{
+    conn: Mutex<Connection>,
+}
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS accounts (
+        id TEXT PRIMARY KEY NOT NULL,
+        name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS account_auth (
+        account TEXT PRIMARY KEY NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS pending_registrations (
+        name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS nick_registrations (
+        id TEXT PRIMARY KEY NOT NULL,
+        nick TEXT NOT NULL UNIQUE COLLATE NOCASE,
+        account TEXT NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS channel_registrations (
+        id TEXT PRIMARY KEY NOT NULL,
+        channelname TEXT NOT NULL UNIQUE COLLATE NOCASE,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS channel_roles (
+        id TEXT PRIMARY KEY NOT NULL,
+        channel TEXT,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS channel_accesses (
+        id TEXT PRIMARY KEY NOT NULL,
+        account TEXT NOT NULL,
+        channel TEXT NOT NULL,
+        data TEXT NOT NULL
+    );
+";
+
+fn to_json(value: &impl Serialize) -> Result<String>
+{
+    serde_json::to_string(value).map_err(DatabaseError::from_inner)
+}
+
+fn from_json<T: DeserializeOwned>(value: String) -> Result<T>
+{
+    serde_json::from_str(&value).map_err(|_| DatabaseError::InvalidData)
+}
+
+/// Translate an SQLite error, mapping primary key and uniqueness constraint violations
+/// to the corresponding [`DatabaseError`] variants
+fn translate_error(error: rusqlite::Error) -> DatabaseError
+{
+    match &error
+    {
+        rusqlite::Error::SqliteFailure(e, _) if e.code == ErrorCode::ConstraintViolation =>
+        {
+            match e.extended_code
+            {
+                rusqlite::ffi::SQLITE_CONSTRAINT_PRIMARYKEY => DatabaseError::DuplicateId,
+                rusqlite::ffi::SQLITE_CONSTRAINT_UNIQUE => DatabaseError::DuplicateName,
+                _ => DatabaseError::from_inner(error)
+            }
+        }
+        _ => DatabaseError::from_inner(error)
+    }
+}
+
+impl SqliteDatabase
+{
+    /// Run `f` inside a transaction, committing if it succeeds
+    fn write<T>(&self, f: impl FnOnce(&Transaction) -> Result<T>) -> Result<T>
+    {
+        let mut conn = self.conn.lock();
+        let txn = conn.transaction().map_err(translate_error)?;
+        let ret = f(&txn)?;
+        txn.commit().map_err(translate_error)?;
+        Ok(ret)
+    }
+
+    /// Fetch a single object by the value of a key column
+    fn get_one<T: DeserializeOwned>(&self, table: &str, column: &str, key: String) -> Result<T>
+    {
+        let data: Option<String> = self.conn.lock()
+                .query_row(&format!("SELECT data FROM {} WHERE {} = ?1", table, column), [key], |row| row.get(0))
+                .optional()
+                .map_err(translate_error)?;
+
+        from_json(data.ok_or(DatabaseError::NoSuchId)?)
+    }
+
+    /// Fetch every object in a table
+    fn get_all<T: DeserializeOwned>(&self, table: &str) -> Result<std::vec::IntoIter<T>>
+    {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(&format!("SELECT data FROM {}", table)).map_err(translate_error)?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0)).map_err(translate_error)?;
+
+        let mut ret = Vec::new();
+        for row in rows
+        {
+            ret.push(from_json(row.map_err(translate_error)?)?);
+        }
+        Ok(ret.into_iter())
+    }
+
+    /// Check that an update statement touched exactly one row
+    fn expect_updated(count: usize) -> Result<()>
+    {
+        if count == 0 { Err(DatabaseError::NoSuchId) } else { Ok(()) }
+    }
+}
+
+impl DatabaseConnection for SqliteDatabase
+{
+    fn connect(conn: impl AsRef<str>) -> Result<Self>
+    {
+        let conn = Connection::open(Path::new(conn.as_ref())).map_err(translate_error)?;
+
+        conn.execute_batch("PRAGMA foreign_keys = ON;").map_err(translate_error)?;
+        conn.execute_batch(SCHEMA).map_err(translate_error)?;
+
+        Ok(Self { conn: Mutex::new(conn) })
+    }
+
+    fn new_account(&self, data: state::Account, mut auth: AccountAuth) -> Result<state::Account>
+    {
+        // Just in case
+        auth.account = data.id;
+
+        self.write(|txn| {
+            txn.execute("INSERT INTO accounts (id, name, data) VALUES (?1, ?2, ?3)",
+                        params![to_json(&data.id)?, data.name.as_ref(), to_json(&data)?])
+               .map_err(translate_error)?;
+            txn.execute("INSERT INTO account_auth (account, data) VALUES (?1, ?2)",
+                        params![to_json(&data.id)?, to_json(&auth)?])
+               .map_err(translate_error)?;
+            Ok(())
+        })?;
+
+        Ok(data)
+    }
+
+    fn account(&self, id: AccountId) -> Result<state::Account>
+    {
+        self.get_one("accounts", "id", to_json(&id)?)
+    }
+
+    fn account_named(&self, name: &Nickname) -> Result<state::Account>
+    {
+        self.get_one("accounts", "name", name.to_string())
+    }
+
+    fn update_account(&self, new_data: &state::Account) -> Result<()>
+    {
+        self.write(|txn| {
+            let count = txn.execute("UPDATE accounts SET name = ?2, data = ?3 WHERE id = ?1",
+                                    params![to_json(&new_data.id)?, new_data.name.as_ref(), to_json(new_data)?])
+                           .map_err(translate_error)?;
+            Self::expect_updated(count)
+        })
+    }
+
+    fn all_accounts(&self) -> Result<impl Iterator<Item=state::Account> + '_>
+    {
+        self.get_all("accounts")
+    }
+
+    fn remove_account(&self, id: AccountId) -> Result<()>
+    {
+        self.write(|txn| {
+            txn.execute("DELETE FROM accounts WHERE id = ?1", [to_json(&id)?]).map_err(translate_error)?;
+            Ok(())
+        })
+    }
+
+    fn auth_for_account(&self, id: AccountId) -> Result<AccountAuth>
+    {
+        self.get_one("account_auth", "account", to_json(&id)?)
+    }
+
+    fn update_auth(&self, new_data: &AccountAuth) -> Result<()>
+    {
+        self.write(|txn| {
+            let count = txn.execute("UPDATE account_auth SET data = ?2 WHERE account = ?1",
+                                    params![to_json(&new_data.account)?, to_json(new_data)?])
+                           .map_err(translate_error)?;
+            Self::expect_updated(count)
+        })
+    }
+
+    fn new_pending_registration(&self, data: PendingRegistration) -> Result<PendingRegistration>
+    {
+        self.write(|txn| {
+            txn.execute("INSERT INTO pending_registrations (name, data) VALUES (?1, ?2)",
+                        params![data.name.as_ref(), to_json(&data)?])
+               .map_err(translate_error)?;
+            Ok(())
+        })?;
+
+        Ok(data)
+    }
+
+    fn pending_registration(&self, name: &Nickname) -> Result<PendingRegistration>
+    {
+        self.get_one("pending_registrations", "name", name.to_string())
+    }
+
+    fn all_pending_registrations(&self) -> Result<impl Iterator<Item=PendingRegistration> + '_>
+    {
+        self.get_all("pending_registrations")
+    }
+
+    fn remove_pending_registration(&self, name: &Nickname) -> Result<()>
+    {
+        self.write(|txn| {
+            txn.execute("DELETE FROM pending_registrations WHERE name = ?1", [name.as_ref()]).map_err(translate_error)?;
+            Ok(())
+        })
+    }
+
+    fn new_nick_registration(&self, data: state::NickRegistration) -> Result<state::NickRegistration>
+    {
+        self.write(|txn| {
+            txn.execute("INSERT INTO nick_registrations (id, nick, account, data) VALUES (?1, ?2, ?3, ?4)",
+                        params![to_json(&data.id)?, data.nick.as_ref(), to_json(&data.account)?, to_json(&data)?])
+               .map_err(translate_error)?;
+            Ok(())
+        })?;
+
+        Ok(data)
+    }
+
+    fn nick_registration(&self, id: NickRegistrationId) -> Result<state::NickRegistration>
+    {
+        self.get_one("nick_registrations", "id", to_json(&id)?)
+    }
+
+    fn update_nick_registration(&self, new_data: &state::NickRegistration) -> Result<()>
+    {
+        self.write(|txn| {
+            let count = txn.execute("UPDATE nick_registrations SET nick = ?2, account = ?3, data = ?4 WHERE id = ?1",
+                                    params![to_json(&new_data.id)?, new_data.nick.as_ref(), to_json(&new_data.account)?, to_json(new_data)?])
+                           .map_err(translate_error)?;
+            Self::expect_updated(count)
+        })
+    }
+
+    fn all_nick_registrations(&self) -> Result<impl Iterator<Item=state::NickRegistration> + '_>
+    {
+        self.get_all("nick_registrations")
+    }
+
+    fn remove_nick_registration(&self, id: NickRegistrationId) -> Result<()>
+    {
+        self.write(|txn| {
+            txn.execute("DELETE FROM nick_registrations WHERE id = ?1", [to_json(&id)?]).map_err(translate_error)?;
+            Ok(())
+        })
+    }
+
+    fn new_channel_registration(&self, data: state::ChannelRegistration) -> Result<state::ChannelRegistration>
+    {
+        self.write(|txn| {
+            txn.execute("INSERT INTO channel_registrations (id, channelname, data) VALUES (?1, ?2, ?3)",
+                        params![to_json(&data.id)?, data.channelname.as_ref(), to_json(&data)?])
+               .map_err(translate_error)?;
+            Ok(())
+        })?;
+
+        Ok(data)
+    }
+
+    fn channel_registration(&self, id: ChannelRegistrationId) -> Result<state::ChannelRegistration>
+    {
+        self.get_one("channel_registrations", "id", to_json(&id)?)
+    }
+
+    fn update_channel_registration(&self, new_data: &state::ChannelRegistration) -> Result<()>
+    {
+        self.write(|txn| {
+            let count = txn.execute("UPDATE channel_registrations SET channelname = ?2, data = ?3 WHERE id = ?1",
+                                    params![to_json(&new_data.id)?, new_data.channelname.as_ref(), to_json(new_data)?])
+                           .map_err(translate_error)?;
+            Self::expect_updated(count)
+        })
+    }
+
+    fn all_channel_registrations(&self) -> Result<impl Iterator<Item=state::ChannelRegistration> + '_>
+    {
+        self.get_all("channel_registrations")
+    }
+
+    fn remove_channel_registration(&self, id: ChannelRegistrationId) -> Result<()>
+    {
+        self.write(|txn| {
+            txn.execute("DELETE FROM channel_registrations WHERE id = ?1", [to_json(&id)?]).map_err(translate_error)?;
+            Ok(())
+        })
+    }
+
+    fn new_channel_role(&self, data: state::ChannelRole) -> Result<state::ChannelRole>
+    {
+        self.write(|txn| {
+            txn.execute("INSERT INTO channel_roles (id, channel, data) VALUES (?1, ?2, ?3)",
+                        params![to_json(&data.id)?, data.channel.map(|c| to_json(&c)).transpose()?, to_json(&data)?])
+               .map_err(translate_error)?;
+            Ok(())
+        })?;
+
+        Ok(data)
+    }
+
+    fn channel_role(&self, id: ChannelRoleId) -> Result<state::ChannelRole>
+    {
+        self.get_one("channel_roles", "id", to_json(&id)?)
+    }
+
+    fn update_channel_role(&self, data: &state::ChannelRole) -> Result<()>
+    {
+        self.write(|txn| {
+            let count = txn.execute("UPDATE channel_roles SET channel = ?2, data = ?3 WHERE id = ?1",
+                                    params![to_json(&data.id)?, data.channel.map(|c| to_json(&c)).transpose()?, to_json(data)?])
+                           .map_err(translate_error)?;
+            Self::expect_updated(count)
+        })
+    }
+
+    fn all_channel_roles(&self) -> Result<impl Iterator<Item=state::ChannelRole> + '_>
+    {
+        self.get_all("channel_roles")
+    }
+
+    fn remove_channel_role(&self, id: ChannelRoleId) -> Result<()>
+    {
+        self.write(|txn| {
+            txn.execute("DELETE FROM channel_roles WHERE id = ?1", [to_json(&id)?]).map_err(translate_error)?;
+            Ok(())
+        })
+    }
+
+    fn update_channel_access(&self, data: &state::ChannelAccess) -> Result<()>
+    {
+        self.write(|txn| {
+            txn.execute("INSERT INTO channel_accesses (id, account, channel, data) VALUES (?1, ?2, ?3, ?4)
+                         ON CONFLICT(id) DO UPDATE SET data = excluded.data",
+                        params![to_json(&data.id)?, to_json(&data.id.account())?, to_json(&data.id.channel())?, to_json(data)?])
+               .map_err(translate_error)?;
+            Ok(())
+        })
+    }
+
+    fn channel_access(&self, id: ChannelAccessId) -> Result<state::ChannelAccess>
+    {
+        self.get_one("channel_accesses", "id", to_json(&id)?)
+    }
+
+    fn all_channel_accesses(&self) -> Result<impl Iterator<Item=state::ChannelAccess> + '_>
+    {
+        self.get_all("channel_accesses")
+    }
+
+    fn remove_channel_access(&self, id: ChannelAccessId) -> Result<()>
+    {
+        self.write(|txn| {
+            txn.execute("DELETE FROM channel_accesses WHERE id = ?1", [to_json(&id)?]).map_err(translate_error)?;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use std::str::FromStr;
+
+    fn account(id: i64, name: &str) -> (state::Account, AccountAuth)
+    {
+        let id = AccountId::new(ServerId::new(1), EpochId::new(1), id);
+        let account = state::Account { id, name: Nickname::from_str(name).unwrap(), authorised_fingerprints: vec!["abc".to_owned()] };
+        let auth = AccountAuth { account: id, password_hash: "hash".to_owned(), scram_sha256: None, recovery_token: None, email: None };
+        (account, auth)
+    }
+
+    #[test]
+    fn account_round_trip()
+    {
+        let db = SqliteDatabase::connect(":memory:").unwrap();
+
+        let (acc, auth) = account(1, "Alice");
+        db.new_account(acc.clone(), auth).unwrap();
+
+        assert_eq!(db.account(acc.id).unwrap(), acc);
+        assert_eq!(db.account_named(&Nickname::from_str("alice").unwrap()).unwrap(), acc);
+        assert_eq!(db.auth_for_account(acc.id).unwrap().password_hash, "hash");
+        assert_eq!(db.all_accounts().unwrap().count(), 1);
+    }
+
+    #[test]
+    fn duplicate_accounts()
+    {
+        let db = SqliteDatabase::connect(":memory:").unwrap();
+
+        let (acc, auth) = account(1, "alice");
+        db.new_account(acc, auth).unwrap();
+
+        let (acc, auth) = account(2, "ALICE");
+        assert!(matches!(db.new_account(acc, auth), Err(DatabaseError::DuplicateName)));
+
+        let (acc, auth) = account(1, "bob");
+        assert!(matches!(db.new_account(acc, auth), Err(DatabaseError::DuplicateId)));
+
+        // The failed inserts must not have left partial data behind
+        assert_eq!(db.all_accounts().unwrap().count(), 1);
+    }
+}
@@ -1,13 +1,57 @@
 use sable_network::{
     id::*,
+    validated::Nickname,
 };
 use serde::{Serialize,Deserialize};
 
-#[derive(Clone,Serialize,Deserialize)]
+#[derive(Clone,PartialEq,Serialize,Deserialize)]
 pub struct AccountAuth
 {
     pub account: AccountId,
     pub password_hash: String,
+    /// Salted SCRAM-SHA-256 keys. Accounts created before these were stored won't have
+    /// them until the next successful password login.
+    #[serde(default)]
+    pub scram_sha256: Option<ScramCredentials>,
+    /// Outstanding password recovery token, if one has been issued
+    #[serde(default)]
+    pub recovery_token: Option<RecoveryToken>,
+    /// Email address given at registration, if any
+    #[serde(default)]
+    pub email: Option<String>,
+}
+
+/// An account registration which is waiting for the user to verify their email address
+#[derive(Clone,PartialEq,Serialize,Deserialize)]
+pub struct PendingRegistration
+{
+    /// The requested account name
+    pub name: Nickname,
+    pub auth: AccountAuth,
+    /// bcrypt hash of the code sent to the user's email address
+    pub code_hash: String,
+    /// Unix timestamp after which the code is no longer valid
+    pub expires: i64,
+}
+
+/// A one-time token allowing an account's password to be reset
+#[derive(Clone,PartialEq,Serialize,Deserialize)]
+pub struct RecoveryToken
+{
+    /// bcrypt hash of the token
+    pub token_hash: String,
+    /// Unix timestamp after which the token is no longer valid
+    pub expires: i64,
+}
+
+/// Stored credentials for a SCRAM mechanism, as defined in RFC 5802
+#[derive(Clone,PartialEq,Serialize,Deserialize)]
+pub struct ScramCredentials
+{
+    pub salt: Vec<u8>,
+    pub iterations: u32,
+    pub stored_key: Vec<u8>,
+    pub server_key: Vec<u8>,
 }
 
 #[derive(Clone,Serialize,Deserialize)]
@@ -15,4 +59,29 @@ pub struct SaslSession
 {
     pub id: SaslSessionId,
     pub mechanism: String,
+    /// TLS client certificate fingerprint of the authenticating connection, if any
+    pub fingerprint: Option<String>,
+    /// Progress of a SCRAM exchange, if one is under way
+    pub scram: Option<ScramSessionState>,
+}
+
+/// Intermediate state of a SCRAM exchange
+#[derive(Clone,Serialize,Deserialize)]
+pub enum ScramSessionState
+{
+    /// The server-first message has been sent; waiting for the client's proof
+    AwaitingProof {
+        /// `None` if the account doesn't exist or has no SCRAM credentials. The exchange
+        /// carries on regardless, so that it doesn't reveal which accounts exist.
+        account: Option<AccountId>,
+        nonce: String,
+        gs2_header: String,
+        client_first_bare: String,
+        server_first: String,
+    },
+    /// The client's proof has been accepted and the server signature sent; waiting for
+    /// the client to acknowledge it
+    AwaitingAck {
+        account: AccountId,
+    },
 }
\ No newline at end of file
@@ -0,0 +1,149 @@
+use super::*;
+
+use sable_network::network::{
+    OptionChange,
+    update::{self, HistoricMessageSource},
+    wrapper::ChannelRegistration,
+};
+use sable_network::prelude::ChannelModeSet;
+
+impl<DB> ServicesServer<DB>
+{
+    /// Apply or enforce the persistent settings stored in a channel's registration in response
+    /// to a change in the channel's state
+    pub(super) fn handle_channel_update(&self, update: &NetworkStateChange)
+    {
+        let net = self.node.network();
+
+        // Only the active services node should act, to avoid duplicate events
+        if net.current_services().map(|s| s.server_id()) != Some(self.node.id())
+        {
+            return;
+        }
+
+        match update
+        {
+            NetworkStateChange::NewChannel(detail) =>
+            {
+                let Ok(channel) = net.channel(detail.channel.id) else { return };
+                let Some(registration) = channel.is_registered() else { return };
+
+                self.apply_channel_settings(detail.channel.id, &registration);
+            }
+            NetworkStateChange::ChannelModeChange(detail) if !self.is_own_change(&detail.changed_by) =>
+            {
+                let Ok(channel) = net.channel(detail.channel.id) else { return };
+                let Some(registration) = channel.is_registered() else { return };
+
+                self.enforce_mode_lock(detail, &registration);
+            }
+            NetworkStateChange::ChannelTopicChange(detail) if !self.is_own_change(&detail.setter) =>
+            {
+                let Ok(channel) = net.channel(detail.channel.id) else { return };
+                let Some(registration) = channel.is_registered() else { return };
+
+                if let Some(topic) = registration.topic_lock().filter(|topic| *topic != detail.new_text)
+                {
+                    tracing::debug!(channel=?channel.name(), "restoring locked topic");
+                    self.set_topic(detail.channel.id, topic);
+                }
+            }
+            _ => ()
+        }
+    }
+
+    /// Whether a change was made by this services instance, and so doesn't need enforcing
+    fn is_own_change(&self, source: &HistoricMessageSource) -> bool
+    {
+        matches!(source, HistoricMessageSource::Server(server) if server.id == self.node.id())
+    }
+
+    /// Apply all of a registered channel's settings to a newly created channel
+    fn apply_channel_settings(&self, channel_id: ChannelId, registration: &ChannelRegistration)
+    {
+        tracing::debug!(channel=?registration.name(), "applying settings to recreated channel");
+
+        let source = ObjectId::Server(self.node.id());
+
+        if let Some(mode_lock) = registration.mode_lock()
+        {
+            let key_change = match mode_lock.key
+            {
+                Some(key) => OptionChange::Set(key),
+                None => OptionChange::NoChange,
+            };
+
+            self.node.submit_event(channel_id, ChannelModeChange {
+                changed_by: source,
+                added: mode_lock.added,
+                removed: mode_lock.removed,
+                key_change
+            });
+        }
+
+        if let Some(topic) = registration.topic_lock()
+        {
+            self.set_topic(channel_id, topic);
+        }
+
+        for entry in registration.list_entries()
+        {
+            self.node.submit_event(self.node.ids().next_list_mode_entry(), NewListModeEntry {
+                list: ListModeId::new(channel_id, entry.list_type),
+                pattern: entry.pattern.clone(),
+                setter: source
+            });
+        }
+    }
+
+    /// Revert any part of a mode change which conflicts with the channel's mode lock
+    fn enforce_mode_lock(&self, change: &update::ChannelModeChange, registration: &ChannelRegistration)
+    {
+        let Some(mode_lock) = registration.mode_lock() else { return };
+
+        let mut readd = ChannelModeSet::new();
+        let mut reremove = ChannelModeSet::new();
+
+        for (flag, _) in ChannelModeSet::all()
+        {
+            if change.removed.is_set(flag) && mode_lock.added.is_set(flag)
+            {
+                readd |= flag;
+            }
+            if change.added.is_set(flag) && mode_lock.removed.is_set(flag)
+            {
+                reremove |= flag;
+            }
+        }
+
+        let key_change = match (&mode_lock.key, &change.key_change)
+        {
+            (Some(key), OptionChange::Unset) => OptionChange::Set(*key),
+            (Some(key), OptionChange::Set(new_key)) if new_key != key => OptionChange::Set(*key),
+            _ => OptionChange::NoChange,
+        };
+
+        if readd.is_empty() && reremove.is_empty() && matches!(key_change, OptionChange::NoChange)
+        {
+            return;
+        }
+
+        tracing::debug!(channel=?registration.name(), "restoring locked modes");
+
+        self.node.submit_event(change.channel.id, ChannelModeChange {
+            changed_by: ObjectId::Server(self.node.id()),
+            added: readd,
+            removed: reremove,
+            key_change
+        });
+    }
+
+    fn set_topic(&self, channel_id: ChannelId, topic: &str)
+    {
+        self.node.submit_event(self.node.ids().next_channel_topic(), NewChannelTopic {
+            channel: channel_id,
+            text: topic.to_owned(),
+            setter: ObjectId::Server(self.node.id())
+        });
+    }
+}
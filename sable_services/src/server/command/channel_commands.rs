@@ -12,7 +12,11 @@ impl<DB: DatabaseConnection> ServicesServer<DB>
 
         let new_channel_registration = state::ChannelRegistration {
             id: self.node.ids().next_channel_registration(),
-            channelname: channel.name().clone()
+            channelname: channel.name().clone(),
+            topic_lock: None,
+            mode_lock: None,
+            entry_message: None,
+            list_entries: Vec::new(),
         };
 
         let new_channel_registration = self.db.new_channel_registration(new_channel_registration)?;
@@ -48,6 +52,34 @@ impl<DB: DatabaseConnection> ServicesServer<DB>
         Ok(RemoteServerResponse::Success)
     }
 
+    /// Remove a channel registration, along with all of its accesses and roles
+    pub(crate) fn drop_channel_registration(&self, registration_id: ChannelRegistrationId) -> Result<(), CommandError>
+    {
+        let accesses: Vec<_> = self.db.all_channel_accesses()?
+                                      .filter(|access| access.id.channel() == registration_id)
+                                      .collect();
+        let roles: Vec<_> = self.db.all_channel_roles()?
+                                   .filter(|role| role.channel == Some(registration_id))
+                                   .collect();
+
+        for access in accesses
+        {
+            self.db.remove_channel_access(access.id)?;
+            self.node.submit_event(access.id, ChannelAccessUpdate { data: None });
+        }
+
+        for role in roles
+        {
+            self.db.remove_channel_role(role.id)?;
+            self.node.submit_event(role.id, ChannelRoleUpdate { data: None });
+        }
+
+        self.db.remove_channel_registration(registration_id)?;
+        self.node.submit_event(registration_id, ChannelRegistrationUpdate { data: None });
+
+        Ok(())
+    }
+
     pub(crate) fn modify_channel_access(&self, source: AccountId, access_id: ChannelAccessId, role: Option<ChannelRoleId>) -> CommandResult
     {
         let net = self.node.network();
@@ -183,4 +215,40 @@ impl<DB: DatabaseConnection> ServicesServer<DB>
 
         Ok(RemoteServerResponse::Success)
     }
-}
\ No newline at end of file
+
+    pub(crate) fn modify_channel_settings(&self, source: AccountId, channel: ChannelRegistrationId, change: ChannelSettingChange) -> CommandResult
+    {
+        let net = self.node.network();
+
+        let source = net.account(source)?;
+        let channel = net.channel_registration(channel)?;
+
+        match source.has_access_in(channel.id())
+        {
+            None => { return Err(RemoteServerResponse::AccessDenied.into()); }
+            Some(access) =>
+            {
+                if ! access.role()?.flags().is_set(ChannelAccessFlag::Founder)
+                {
+                    return Err(RemoteServerResponse::AccessDenied.into());
+                }
+            }
+        };
+
+        let mut registration = self.db.channel_registration(channel.id())?;
+
+        match change
+        {
+            ChannelSettingChange::TopicLock(topic) => registration.topic_lock = topic,
+            ChannelSettingChange::ModeLock(mode_lock) => registration.mode_lock = mode_lock,
+            ChannelSettingChange::EntryMessage(message) => registration.entry_message = message,
+            ChannelSettingChange::ListEntries(entries) => registration.list_entries = entries,
+        }
+
+        self.db.update_channel_registration(&registration)?;
+
+        self.node.submit_event(registration.id, ChannelRegistrationUpdate { data: Some(registration) });
+
+        Ok(RemoteServerResponse::Success)
+    }
+}
@@ -4,7 +4,7 @@ use RemoteServerResponse::Authenticate;
 
 impl<DB: DatabaseConnection> ServicesServer<DB>
 {
-    pub fn begin_authenticate(&self, session: SaslSessionId, mechanism: String) -> CommandResult
+    pub fn begin_authenticate(&self, session: SaslSessionId, mechanism: String, fingerprint: Option<String>) -> CommandResult
     {
         if self.sasl_sessions.contains_key(&session)
         {
@@ -16,13 +16,13 @@ impl<DB: DatabaseConnection> ServicesServer<DB>
             return Ok(Authenticate(Aborted));
         }
 
-        self.sasl_sessions.insert(session, SaslSession { id: session, mechanism});
+        self.sasl_sessions.insert(session, SaslSession { id: session, mechanism, fingerprint, scram: None });
         Ok(Authenticate(InProgress(Vec::new())))
     }
 
     pub fn authenticate(&self, session_id: SaslSessionId, data: Vec<u8>) -> CommandResult
     {
-        let Some(session) = self.sasl_sessions.get(&session_id) else {
+        let Some(mut session) = self.sasl_sessions.get_mut(&session_id) else {
             return Ok(Authenticate(Aborted));
         };
 
@@ -31,7 +31,7 @@ impl<DB: DatabaseConnection> ServicesServer<DB>
             return Ok(Authenticate(Aborted));
         };
 
-        let response = mechanism.step(self, &session, data)?;
+        let response = mechanism.step(self, &mut session, data)?;
 
         Ok(Authenticate(response))
     }
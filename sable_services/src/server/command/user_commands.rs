@@ -1,26 +1,132 @@
 use super::*;
+use rand::{Rng, distributions::Alphanumeric};
+use sable_network::network::state::ChannelRoleName;
+
+/// How long a password recovery token remains valid, in seconds
+const RECOVERY_TOKEN_LIFETIME: i64 = 3600;
+
+/// How long an emailed account verification code remains valid, in seconds
+const VERIFICATION_CODE_LIFETIME: i64 = 24 * 3600;
 
 impl<DB: DatabaseConnection> ServicesServer<DB>
 {
-    pub(crate) fn register_user(&self, account_name: Nickname, password: String) -> CommandResult
+    pub(crate) fn register_user(&self, account_name: Nickname, password: String, email: Option<String>) -> CommandResult
     {
-        let new_account_id = self.node.ids().next_account();
+        let now = sable_network::utils::now();
 
-        let Ok(password_hash) = bcrypt::hash(password, bcrypt::DEFAULT_COST) else {
+        // Clear out registrations which were never verified. Collect them first, since some
+        // backends hold a lock while iterating
+        let expired: Vec<_> = self.db.all_pending_registrations()?
+                                     .filter(|pending| pending.expires <= now)
+                                     .map(|pending| pending.name)
+                                     .collect();
+        for name in expired
+        {
+            self.db.remove_pending_registration(&name)?;
+        }
+
+        // The account name is registered as a nick, so it can't belong to someone else already
+        if self.db.all_nick_registrations()?.any(|reg| reg.nick == account_name)
+        {
+            tracing::debug!(?account_name, "Account name is registered as a nick");
+            return Ok(RemoteServerResponse::AlreadyExists);
+        }
+
+        if self.db.pending_registration(&account_name).is_ok()
+        {
+            tracing::debug!(?account_name, "Account name has a pending registration");
+            return Ok(RemoteServerResponse::AlreadyExists);
+        }
+
+        if email.as_deref().map(|e| !mailer::is_valid_address(e)).unwrap_or(false)
+            || (self.mailer.is_some() && email.is_none())
+        {
+            return Ok(RemoteServerResponse::InvalidEmail);
+        }
+
+        let Ok(password_hash) = bcrypt::hash(&password, bcrypt::DEFAULT_COST) else {
             tracing::error!(?account_name, "Failed to hash password for new account");
 
             return Err("Failed to hash password".into());
         };
 
+        let auth_data = AccountAuth {
+            account: self.node.ids().next_account(),
+            password_hash,
+            scram_sha256: Some(sasl::scram_credentials(password.as_bytes())),
+            recovery_token: None,
+            email,
+        };
+
+        match (&self.mailer, &auth_data.email)
+        {
+            (Some(mailer), Some(email)) =>
+            {
+                if self.db.account_named(&account_name).is_ok()
+                {
+                    return Ok(RemoteServerResponse::AlreadyExists);
+                }
+
+                let code: String = rand::thread_rng().sample_iter(&Alphanumeric).take(12).map(char::from).collect();
+                let body = format!("Your verification code for the account {} is {}\n\n\
+                                    To complete registration, use: /VERIFY {} {}\n",
+                                   account_name, code, account_name, code);
+
+                let Ok(code_hash) = bcrypt::hash(&code, bcrypt::DEFAULT_COST) else {
+                    return Err("Failed to hash verification code".into());
+                };
+
+                let email = email.clone();
+
+                self.db.new_pending_registration(PendingRegistration {
+                    name: account_name,
+                    auth: auth_data,
+                    code_hash,
+                    expires: now + VERIFICATION_CODE_LIFETIME,
+                })?;
+
+                // Delivery happens in the background; if it fails, the user can register
+                // again once the pending registration expires
+                if let Err(error) = mailer.queue(&email, "Account verification", &body)
+                {
+                    tracing::error!(?error, ?account_name, "Failed to queue verification email");
+                    self.db.remove_pending_registration(&account_name)?;
+                    return Err("Couldn't send verification email".into());
+                }
+
+                Ok(RemoteServerResponse::VerificationRequired)
+            }
+            _ => self.create_account(account_name, auth_data)
+        }
+    }
+
+    pub(crate) fn verify_account(&self, account_name: Nickname, code: String) -> CommandResult
+    {
+        let now = sable_network::utils::now();
+
+        let pending = match self.db.pending_registration(&account_name)
+        {
+            Ok(pending) if pending.expires > now
+                            && bcrypt::verify(&code, &pending.code_hash).unwrap_or(false) => pending,
+            _ =>
+            {
+                tracing::debug!(?account_name, "invalid or expired verification code");
+                return Ok(RemoteServerResponse::InvalidCredentials);
+            }
+        };
+
+        self.db.remove_pending_registration(&account_name)?;
+        self.create_account(account_name, pending.auth)
+    }
+
+    /// Store a new account and propagate it to the network
+    fn create_account(&self, account_name: Nickname, auth_data: AccountAuth) -> CommandResult
+    {
         let account_data = state::Account {
-            id: new_account_id,
+            id: auth_data.account,
             name: account_name,
             authorised_fingerprints: Vec::new(),
         };
-        let auth_data = AccountAuth {
-            account: new_account_id,
-            password_hash
-        };
 
         match self.db.new_account(account_data, auth_data)
         {
@@ -29,6 +135,7 @@ impl<DB: DatabaseConnection> ServicesServer<DB>
                 tracing::debug!(?new_account, "Successfully created account");
                 let id = new_account.id;
                 self.node.submit_event(id, AccountUpdate { data: Some(new_account) });
+                self.group_nick(id, account_name)?;
                 Ok(RemoteServerResponse::LogUserIn(id))
             }
             Err(DatabaseError::DuplicateId | DatabaseError::DuplicateName) =>
@@ -51,10 +158,11 @@ impl<DB: DatabaseConnection> ServicesServer<DB>
             return Err("Couldn't look up account".into());
         };
 
-        match bcrypt::verify(password, &auth.password_hash)
+        match bcrypt::verify(&password, &auth.password_hash)
         {
             Ok(true) => {
                 tracing::debug!("login successful");
+                self.upgrade_auth(auth, password.as_bytes());
                 Ok(RemoteServerResponse::LogUserIn(account_id))
             }
             Ok(false) => {
@@ -65,6 +173,254 @@ impl<DB: DatabaseConnection> ServicesServer<DB>
         }
     }
 
+    pub(crate) fn group_nick(&self, account_id: AccountId, nick: Nickname) -> CommandResult
+    {
+        if self.db.all_nick_registrations()?.any(|reg| reg.nick == nick)
+        {
+            return Ok(RemoteServerResponse::AlreadyExists);
+        }
+
+        let registration = state::NickRegistration {
+            id: self.node.ids().next_nick_registration(),
+            nick,
+            account: account_id,
+        };
+
+        match self.db.new_nick_registration(registration)
+        {
+            Ok(registration) =>
+            {
+                tracing::debug!(?registration, "Registered nick");
+                self.node.submit_event(registration.id, NickRegistrationUpdate { data: Some(registration) });
+                Ok(RemoteServerResponse::Success)
+            }
+            Err(DatabaseError::DuplicateId | DatabaseError::DuplicateName) =>
+            {
+                Ok(RemoteServerResponse::AlreadyExists)
+            }
+            Err(error) => Err(error.into())
+        }
+    }
+
+    pub(crate) fn ungroup_nick(&self, account_id: AccountId, nick: Nickname) -> CommandResult
+    {
+        let account = self.db.account(account_id)?;
+
+        // The account name itself always stays registered
+        if account.name == nick
+        {
+            return Ok(RemoteServerResponse::AccessDenied);
+        }
+
+        let Some(registration) = self.db.all_nick_registrations()?.find(|reg| reg.nick == nick) else {
+            return Ok(RemoteServerResponse::NoAccount);
+        };
+
+        if registration.account != account_id
+        {
+            return Ok(RemoteServerResponse::AccessDenied);
+        }
+
+        self.db.remove_nick_registration(registration.id)?;
+        self.node.submit_event(registration.id, NickRegistrationUpdate { data: None });
+
+        Ok(RemoteServerResponse::Success)
+    }
+
+    /// Replace the stored credentials in `auth` with ones derived from `password`, invalidating
+    /// any outstanding recovery token
+    /// Passwords are checked by commands which take them as a single word, so one containing
+    /// whitespace could be set but never used
+    fn password_rejection(password: &str) -> Option<RemoteServerResponse>
+    {
+        password.contains(char::is_whitespace)
+                .then(|| RemoteServerResponse::Error("Passwords may not contain spaces".to_owned()))
+    }
+
+    fn set_credentials(auth: &mut AccountAuth, password: &str) -> Result<(), CommandError>
+    {
+        let Ok(password_hash) = bcrypt::hash(password, bcrypt::DEFAULT_COST) else {
+            return Err("Failed to hash password".into());
+        };
+
+        auth.password_hash = password_hash;
+        auth.scram_sha256 = Some(sasl::scram_credentials(password.as_bytes()));
+        auth.recovery_token = None;
+
+        Ok(())
+    }
+
+    pub(crate) fn set_password(&self, account_id: AccountId, password: String) -> CommandResult
+    {
+        let Ok(mut auth) = self.db.auth_for_account(account_id) else {
+            tracing::error!(?account_id, "Error looking up account");
+            return Err("Couldn't look up account".into());
+        };
+
+        if let Some(rejection) = Self::password_rejection(&password)
+        {
+            return Ok(rejection);
+        }
+
+        Self::set_credentials(&mut auth, &password)?;
+        self.db.update_auth(&auth)?;
+
+        tracing::debug!(?account_id, "password changed");
+        Ok(RemoteServerResponse::Success)
+    }
+
+    pub(crate) fn begin_password_recovery(&self, account_id: AccountId) -> CommandResult
+    {
+        let Ok(mut auth) = self.db.auth_for_account(account_id) else {
+            return Ok(RemoteServerResponse::NoAccount);
+        };
+
+        let token: String = rand::thread_rng().sample_iter(&Alphanumeric).take(24).map(char::from).collect();
+
+        let Ok(token_hash) = bcrypt::hash(&token, bcrypt::DEFAULT_COST) else {
+            return Err("Failed to hash recovery token".into());
+        };
+
+        auth.recovery_token = Some(RecoveryToken {
+            token_hash,
+            expires: sable_network::utils::now() + RECOVERY_TOKEN_LIFETIME,
+        });
+        self.db.update_auth(&auth)?;
+
+        Ok(RemoteServerResponse::RecoveryToken(token))
+    }
+
+    pub(crate) fn recover_password(&self, account_id: AccountId, token: String, password: String) -> CommandResult
+    {
+        let Ok(mut auth) = self.db.auth_for_account(account_id) else {
+            return Ok(RemoteServerResponse::NoAccount);
+        };
+
+        let token_valid = match &auth.recovery_token
+        {
+            Some(recovery) => recovery.expires > sable_network::utils::now()
+                                && bcrypt::verify(&token, &recovery.token_hash).unwrap_or(false),
+            None => false
+        };
+
+        if ! token_valid
+        {
+            tracing::debug!(?account_id, "invalid or expired recovery token");
+            return Ok(RemoteServerResponse::InvalidCredentials);
+        }
+
+        // Leave the token in place, so that it can be used again with a valid password
+        if let Some(rejection) = Self::password_rejection(&password)
+        {
+            return Ok(rejection);
+        }
+
+        Self::set_credentials(&mut auth, &password)?;
+        self.db.update_auth(&auth)?;
+
+        tracing::debug!(?account_id, "password reset with recovery token");
+        Ok(RemoteServerResponse::Success)
+    }
+
+    pub(crate) fn drop_account(&self, account_id: AccountId, password: String) -> CommandResult
+    {
+        let Ok(auth) = self.db.auth_for_account(account_id) else {
+            return Ok(RemoteServerResponse::NoAccount);
+        };
+
+        if ! bcrypt::verify(password, &auth.password_hash).unwrap_or(false)
+        {
+            return Ok(RemoteServerResponse::InvalidCredentials);
+        }
+
+        // Collect everything up front, since some backends hold a lock while iterating
+        let nick_registrations: Vec<_> = self.db.all_nick_registrations()?
+                                                 .filter(|reg| reg.account == account_id)
+                                                 .collect();
+        let accesses: Vec<_> = self.db.all_channel_accesses()?
+                                      .filter(|access| access.id.account() == account_id)
+                                      .collect();
+
+        for registration in nick_registrations
+        {
+            self.db.remove_nick_registration(registration.id)?;
+            self.node.submit_event(registration.id, NickRegistrationUpdate { data: None });
+        }
+
+        for access in accesses
+        {
+            let channel = access.id.channel();
+
+            if self.is_only_founder(account_id, &access)?
+            {
+                tracing::debug!(?account_id, ?channel, "dropping channel registration with dropped founder");
+                self.drop_channel_registration(channel)?;
+            }
+            else
+            {
+                self.db.remove_channel_access(access.id)?;
+                self.node.submit_event(access.id, ChannelAccessUpdate { data: None });
+            }
+        }
+
+        // Anyone still logged in to the account needs to be logged out
+        let logged_in_users: Vec<_> = self.node.network().account(account_id)
+                                                         .map(|account| account.users().map(|u| u.id()).collect())
+                                                         .unwrap_or_default();
+        for user in logged_in_users
+        {
+            self.node.submit_event(user, UserLogin { account: None });
+        }
+
+        self.db.remove_account(account_id)?;
+        self.node.submit_event(account_id, AccountUpdate { data: None });
+
+        tracing::debug!(?account_id, "account dropped");
+        Ok(RemoteServerResponse::Success)
+    }
+
+    /// Determine whether `access` grants its account the founder role, with no other
+    /// account holding the founder role in the same channel
+    fn is_only_founder(&self, account_id: AccountId, access: &state::ChannelAccess) -> Result<bool, CommandError>
+    {
+        if self.db.channel_role(access.role)?.name != ChannelRoleName::BuiltinFounder
+        {
+            return Ok(false);
+        }
+
+        let other_founder_roles: Vec<_> = self.db.all_channel_accesses()?
+                .filter(|other| other.id.channel() == access.id.channel() && other.id.account() != account_id)
+                .map(|other| other.role)
+                .collect();
+
+        for role in other_founder_roles
+        {
+            if self.db.channel_role(role)?.name == ChannelRoleName::BuiltinFounder
+            {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Generate any credentials missing from an account's stored authentication data,
+    /// after its password has been verified
+    pub(crate) fn upgrade_auth(&self, mut auth: AccountAuth, password: &[u8])
+    {
+        if auth.scram_sha256.is_some()
+        {
+            return;
+        }
+
+        auth.scram_sha256 = Some(sasl::scram_credentials(password));
+
+        if let Err(error) = self.db.update_auth(&auth)
+        {
+            tracing::error!(?error, account=?auth.account, "Failed to store upgraded credentials");
+        }
+    }
+
     pub(crate) fn user_add_fp(&self, account_id: AccountId, fp: String) -> CommandResult
     {
         if self.node.network().account_with_fingerprint(&fp).is_some()
@@ -100,4 +456,89 @@ impl<DB: DatabaseConnection> ServicesServer<DB>
         Ok(RemoteServerResponse::Success)
     }
 
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::server::test_utils::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn register_and_verify()
+    {
+        let spool = spool_directory("register-and-verify");
+        let server = test_server(&spool);
+        let name = Nickname::from_str("alice").unwrap();
+
+        let response = server.register_user(name, "password".to_owned(), Some("alice@example.com".to_owned())).unwrap();
+        assert!(matches!(response, RemoteServerResponse::VerificationRequired));
+        assert!(server.db.account_named(&name).is_err());
+
+        let code = read_verification_code(&spool);
+
+        // Only a hash of the code is stored
+        assert_ne!(server.db.pending_registration(&name).unwrap().code_hash, code);
+
+        let response = server.verify_account(name, code.clone()).unwrap();
+        let RemoteServerResponse::LogUserIn(account) = response else { panic!("Unexpected response {:?}", response) };
+
+        assert_eq!(server.db.account_named(&name).unwrap().id, account);
+        assert_eq!(server.db.auth_for_account(account).unwrap().email.as_deref(), Some("alice@example.com"));
+
+        // The code can only be used once
+        assert!(matches!(server.verify_account(name, code).unwrap(), RemoteServerResponse::InvalidCredentials));
+
+        std::fs::remove_dir_all(spool).unwrap();
+    }
+
+    #[test]
+    fn verify_with_bad_code()
+    {
+        let spool = spool_directory("verify-with-bad-code");
+        let server = test_server(&spool);
+        let name = Nickname::from_str("bob").unwrap();
+
+        server.register_user(name, "password".to_owned(), Some("bob@example.com".to_owned())).unwrap();
+        let code = read_verification_code(&spool);
+
+        assert!(matches!(server.verify_account(name, "wrong".to_owned()).unwrap(), RemoteServerResponse::InvalidCredentials));
+
+        // A wrong guess doesn't use up the registration, but expiry does
+        let mut pending = server.db.pending_registration(&name).unwrap();
+        pending.expires = sable_network::utils::now() - 1;
+        server.db.remove_pending_registration(&name).unwrap();
+        server.db.new_pending_registration(pending).unwrap();
+
+        assert!(matches!(server.verify_account(name, code).unwrap(), RemoteServerResponse::InvalidCredentials));
+        assert!(server.db.account_named(&name).is_err());
+
+        std::fs::remove_dir_all(spool).unwrap();
+    }
+
+    #[test]
+    fn passwords_with_spaces_are_rejected()
+    {
+        let spool = spool_directory("passwords-with-spaces");
+        let server = test_server(&spool);
+        let name = Nickname::from_str("carol").unwrap();
+
+        server.register_user(name, "password".to_owned(), Some("carol@example.com".to_owned())).unwrap();
+        let code = read_verification_code(&spool);
+        let RemoteServerResponse::LogUserIn(account) = server.verify_account(name, code).unwrap() else { panic!() };
+        let original_hash = server.db.auth_for_account(account).unwrap().password_hash;
+
+        assert!(matches!(server.set_password(account, "two words".to_owned()).unwrap(), RemoteServerResponse::Error(_)));
+        assert_eq!(server.db.auth_for_account(account).unwrap().password_hash, original_hash);
+
+        let RemoteServerResponse::RecoveryToken(token) = server.begin_password_recovery(account).unwrap() else { panic!() };
+        assert!(matches!(server.recover_password(account, token.clone(), "two\twords".to_owned()).unwrap(), RemoteServerResponse::Error(_)));
+        assert_eq!(server.db.auth_for_account(account).unwrap().password_hash, original_hash);
+
+        // The token survives the rejected attempt
+        assert!(matches!(server.recover_password(account, token, "newpassword".to_owned()).unwrap(), RemoteServerResponse::Success));
+        assert!(matches!(server.user_login(account, "newpassword".to_owned()).unwrap(), RemoteServerResponse::LogUserIn(_)));
+
+        std::fs::remove_dir_all(spool).unwrap();
+    }
+}
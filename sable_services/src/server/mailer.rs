@@ -0,0 +1,256 @@
+use rand::{Rng, distributions::Alphanumeric};
+use serde::Deserialize;
+use thiserror::Error;
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpStream,
+    path::PathBuf,
+    sync::mpsc,
+    time::Duration,
+};
+
+/// Timeout applied to each step of an SMTP conversation
+const SMTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug,Error)]
+pub enum MailerError
+{
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Unexpected SMTP response: {0}")]
+    Smtp(String),
+    #[error("Mail queue is not running")]
+    QueueClosed,
+}
+
+/// Trait describing a method of delivering email
+pub trait Mailer : Send + Sync + 'static
+{
+    /// Send a plain text message to the given address
+    fn send(&self, to: &str, subject: &str, body: &str) -> Result<(), MailerError>;
+}
+
+/// Configuration for the mailer used to send account verification emails
+#[derive(Debug,Clone,Deserialize)]
+#[serde(tag="type", rename_all="snake_case")]
+pub enum MailerConfig
+{
+    /// Write each message to a separate file in the given directory, for testing
+    Spool { from: String, directory: PathBuf },
+    /// Send messages through an SMTP relay. No authentication or TLS is used, so this should
+    /// be a relay on the local machine or a trusted network.
+    Smtp { from: String, relay: String },
+}
+
+pub fn build_mailer(config: &MailerConfig) -> Box<dyn Mailer>
+{
+    match config
+    {
+        MailerConfig::Spool { from, directory } => Box::new(SpoolMailer { from: from.clone(), directory: directory.clone() }),
+        MailerConfig::Smtp { from, relay } => Box::new(SmtpMailer { from: from.clone(), relay: relay.clone() }),
+    }
+}
+
+/// A message waiting to be delivered by a [`MailQueue`]
+struct OutgoingMail
+{
+    to: String,
+    subject: String,
+    body: String,
+}
+
+/// Delivers mail on a dedicated thread, so that a slow or unreachable relay doesn't hold up
+/// the services request which generated the message
+pub struct MailQueue
+{
+    sender: mpsc::Sender<OutgoingMail>,
+}
+
+impl MailQueue
+{
+    pub fn new(mailer: Box<dyn Mailer>) -> Self
+    {
+        let (sender, receiver) = mpsc::channel::<OutgoingMail>();
+
+        std::thread::Builder::new()
+            .name("mailer".to_string())
+            .spawn(move || {
+                // Exits when the queue, and with it the sender, is dropped
+                for mail in receiver
+                {
+                    if let Err(error) = mailer.send(&mail.to, &mail.subject, &mail.body)
+                    {
+                        tracing::error!(?error, to=?mail.to, "Failed to send email");
+                    }
+                }
+            })
+            .expect("Couldn't start mailer thread");
+
+        Self { sender }
+    }
+
+    /// Queue a message for delivery. Delivery failures are logged, not reported to the caller.
+    pub fn queue(&self, to: &str, subject: &str, body: &str) -> Result<(), MailerError>
+    {
+        self.sender.send(OutgoingMail { to: to.to_string(), subject: subject.to_string(), body: body.to_string() })
+                   .map_err(|_| MailerError::QueueClosed)
+    }
+}
+
+/// Check that an address is usable as a mail recipient. This is deliberately loose; the
+/// verification email is the real test. It mostly exists to keep line breaks and other
+/// protocol-significant characters out of the SMTP conversation.
+pub fn is_valid_address(address: &str) -> bool
+{
+    match address.split_once('@')
+    {
+        Some((local, domain)) => !local.is_empty()
+                                    && !domain.is_empty()
+                                    && !domain.contains('@')
+                                    && address.chars().all(|c| c.is_ascii_graphic() && c != '<' && c != '>'),
+        None => false
+    }
+}
+
+fn format_message(from: &str, to: &str, subject: &str, body: &str) -> String
+{
+    let mut message = format!("From: {}\r\nTo: {}\r\nSubject: {}\r\n\r\n", from, to, subject);
+
+    for line in body.lines()
+    {
+        // Dot-stuffing, so that a line consisting of "." doesn't end the SMTP DATA section
+        if line.starts_with('.')
+        {
+            message.push('.');
+        }
+        message.push_str(line);
+        message.push_str("\r\n");
+    }
+
+    message
+}
+
+/// A [`Mailer`] which writes messages to files instead of sending them
+pub struct SpoolMailer
+{
+    from: String,
+    directory: PathBuf,
+}
+
+impl Mailer for SpoolMailer
+{
+    fn send(&self, to: &str, subject: &str, body: &str) -> Result<(), MailerError>
+    {
+        // The recipient address isn't safe to use as a path component, so the file is named
+        // with a random identifier instead
+        let id: String = rand::thread_rng().sample_iter(&Alphanumeric).take(16).map(char::from).collect();
+        let filename = format!("{}-{}.eml", sable_network::utils::now(), id);
+        std::fs::create_dir_all(&self.directory)?;
+        std::fs::write(self.directory.join(filename), format_message(&self.from, to, subject, body))?;
+
+        Ok(())
+    }
+}
+
+/// A [`Mailer`] which delivers messages to an SMTP relay
+pub struct SmtpMailer
+{
+    from: String,
+    relay: String,
+}
+
+impl SmtpMailer
+{
+    /// Read a (possibly multi-line) SMTP reply, and check that it has the expected status code
+    fn expect_reply(reader: &mut impl BufRead, expected: &str) -> Result<(), MailerError>
+    {
+        loop
+        {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0
+            {
+                return Err(MailerError::Smtp("connection closed".to_string()));
+            }
+
+            if !line.starts_with(expected)
+            {
+                return Err(MailerError::Smtp(line.trim_end().to_string()));
+            }
+
+            // Continuation lines have a '-' after the status code
+            if line.as_bytes().get(3) != Some(&b'-')
+            {
+                return Ok(());
+            }
+        }
+    }
+
+    fn command(stream: &mut TcpStream, reader: &mut impl BufRead, command: &str, expected: &str) -> Result<(), MailerError>
+    {
+        stream.write_all(command.as_bytes())?;
+        stream.write_all(b"\r\n")?;
+        Self::expect_reply(reader, expected)
+    }
+}
+
+impl Mailer for SmtpMailer
+{
+    fn send(&self, to: &str, subject: &str, body: &str) -> Result<(), MailerError>
+    {
+        let mut stream = TcpStream::connect(&self.relay)?;
+        stream.set_read_timeout(Some(SMTP_TIMEOUT))?;
+        stream.set_write_timeout(Some(SMTP_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+
+        Self::expect_reply(&mut reader, "220")?;
+        Self::command(&mut stream, &mut reader, "HELO localhost", "250")?;
+        Self::command(&mut stream, &mut reader, &format!("MAIL FROM:<{}>", self.from), "250")?;
+        Self::command(&mut stream, &mut reader, &format!("RCPT TO:<{}>", to), "25")?;
+        Self::command(&mut stream, &mut reader, "DATA", "354")?;
+
+        stream.write_all(format_message(&self.from, to, subject, body).as_bytes())?;
+        Self::command(&mut stream, &mut reader, ".", "250")?;
+        Self::command(&mut stream, &mut reader, "QUIT", "221")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn address_validation()
+    {
+        assert!(is_valid_address("user@example.com"));
+        assert!(!is_valid_address("user"));
+        assert!(!is_valid_address("@example.com"));
+        assert!(!is_valid_address("user@a@b"));
+        assert!(!is_valid_address("user@example.com\r\nRCPT TO:<x@y>"));
+    }
+
+    #[test]
+    fn spool_mailer_writes_message()
+    {
+        let directory = std::env::temp_dir().join(format!("sable-spool-test-{}", std::process::id()));
+        let mailer = SpoolMailer { from: "services@example.com".to_string(), directory: directory.clone() };
+
+        mailer.send("user@example.com", "Test", "hello\n.world").unwrap();
+
+        let files: Vec<_> = std::fs::read_dir(&directory).unwrap().collect();
+        assert_eq!(files.len(), 1);
+
+        let contents = std::fs::read_to_string(files[0].as_ref().unwrap().path()).unwrap();
+        assert!(contents.contains("To: user@example.com\r\n"));
+        assert!(contents.ends_with("hello\r\n..world\r\n"));
+
+        // A hostile address mustn't be able to choose where the message is written
+        mailer.send("../../x@example.com", "Test", "hello").unwrap();
+        assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 2);
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
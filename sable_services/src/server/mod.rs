@@ -37,14 +37,29 @@ mod sync;
 mod command;
 mod roles;
 mod sasl;
+mod nick_enforcement;
+mod channel_settings;
+mod mailer;
+
+#[cfg(test)]
+mod test_utils;
 
 #[derive(Deserialize)]
 pub struct ServicesConfig
 {
     pub database: String,
     pub default_roles: HashMap<ChannelRoleName, Vec<ChannelAccessFlag>>,
+    /// How long, in seconds, a user may hold a registered nickname without logging in to
+    /// the owning account before being moved off it
+    #[serde(default = "default_nick_enforcement_grace")]
+    pub nick_enforcement_grace: i64,
+    /// If set, new accounts must verify their email address before registration completes
+    #[serde(default)]
+    pub mailer: Option<mailer::MailerConfig>,
 }
 
+fn default_nick_enforcement_grace() -> i64 { 60 }
+
 pub struct ServicesServer<DB>
 {
     db: DB,
@@ -53,6 +68,7 @@ pub struct ServicesServer<DB>
     config: ServicesConfig,
     sasl_sessions: DashMap<SaslSessionId, SaslSession>,
     sasl_mechanisms: HashMap<String, Box<dyn sasl::SaslMechanism<DB>>>,
+    mailer: Option<mailer::MailQueue>,
 }
 
 #[async_trait]
@@ -72,6 +88,8 @@ impl<DB> ServerType for ServicesServer<DB>
             panic!("Builtin roles not defined");
         }
 
+        let mailer = config.mailer.as_ref().map(|config| mailer::MailQueue::new(mailer::build_mailer(config)));
+
         Self {
             db: DatabaseConnection::connect(&config.database).unwrap(),
             node,
@@ -79,6 +97,7 @@ impl<DB> ServerType for ServicesServer<DB>
             config,
             sasl_sessions: DashMap::new(),
             sasl_mechanisms: sasl::build_mechanisms(),
+            mailer,
         }
     }
 
@@ -87,14 +106,21 @@ impl<DB> ServerType for ServicesServer<DB>
     async fn run(self: Arc<Self>, mut shutdown_channel: broadcast::Receiver<ShutdownAction>)
     {
         let mut history_receiver = self.history_receiver.lock().await;
+        let mut nick_enforcement_timer = tokio::time::interval(tokio::time::Duration::from_secs(10));
 
         loop {
             tokio::select! {
                 _ = shutdown_channel.recv() => { break; }
 
+                _ = nick_enforcement_timer.tick() =>
+                {
+                    self.enforce_nick_registrations();
+                }
+
                 update = history_receiver.recv() =>
                 {
                     let mut do_burst = false;
+                    let mut channel_update = None;
 
                     if let Some(NetworkHistoryUpdate::NewEntry(id)) = update
                     {
@@ -111,9 +137,20 @@ impl<DB> ServerType for ServicesServer<DB>
                                     do_burst = true;
                                 }
                             }
+                            else if matches!(&entry.details, NetworkStateChange::NewChannel(_)
+                                                                | NetworkStateChange::ChannelModeChange(_)
+                                                                | NetworkStateChange::ChannelTopicChange(_))
+                            {
+                                channel_update = Some(entry.details.clone());
+                            }
                         }
                     }
 
+                    if let Some(update) = channel_update
+                    {
+                        self.handle_channel_update(&update);
+                    }
+
                     if do_burst
                     {
                         self.burst_to_network().await;
@@ -138,11 +175,17 @@ impl<DB> ServerType for ServicesServer<DB>
 
         let result = match req
         {
-            RegisterUser(account_name, password) =>
+            RegisterUser(account_name, password, email) =>
             {
                 tracing::debug!(?account_name, "Got register request");
 
-                self.register_user(account_name, password)
+                self.register_user(account_name, password, email)
+            }
+            VerifyAccount(account_name, code) =>
+            {
+                tracing::debug!(?account_name, "Got account verification");
+
+                self.verify_account(account_name, code)
             }
             UserLogin(account_id, password) =>
             {
@@ -150,6 +193,42 @@ impl<DB> ServerType for ServicesServer<DB>
 
                 self.user_login(account_id, password)
             }
+            SetPassword(account, password) =>
+            {
+                tracing::debug!(?account, "Got set password");
+
+                self.set_password(account, password)
+            }
+            BeginPasswordRecovery(account) =>
+            {
+                tracing::debug!(?account, "Got begin password recovery");
+
+                self.begin_password_recovery(account)
+            }
+            RecoverPassword(account, token, password) =>
+            {
+                tracing::debug!(?account, "Got password recovery");
+
+                self.recover_password(account, token, password)
+            }
+            DropAccount(account, password) =>
+            {
+                tracing::debug!(?account, "Got drop account");
+
+                self.drop_account(account, password)
+            }
+            GroupNick(account_id, nick) =>
+            {
+                tracing::debug!(?account_id, ?nick, "Got group nick");
+
+                self.group_nick(account_id, nick)
+            }
+            UngroupNick(account_id, nick) =>
+            {
+                tracing::debug!(?account_id, ?nick, "Got ungroup nick");
+
+                self.ungroup_nick(account_id, nick)
+            }
             RegisterChannel(account_id, channel_id) =>
             {
                 tracing::debug!(?account_id, ?channel_id, "Got channel register request");
@@ -174,11 +253,17 @@ impl<DB> ServerType for ServicesServer<DB>
 
                 self.modify_role(source, id, flags)
             }
-            BeginAuthenticate(session, mechanism) =>
+            ModifyChannelSettings { source, channel, change } =>
+            {
+                tracing::debug!(?source, ?channel, ?change, "Got channel settings change");
+
+                self.modify_channel_settings(source, channel, change)
+            }
+            BeginAuthenticate(session, mechanism, fingerprint) =>
             {
-                tracing::debug!(?session, ?mechanism, "Got begin authenticate");
+                tracing::debug!(?session, ?mechanism, ?fingerprint, "Got begin authenticate");
 
-                self.begin_authenticate(session, mechanism)
+                self.begin_authenticate(session, mechanism, fingerprint)
             }
             Authenticate(session, data) =>
             {
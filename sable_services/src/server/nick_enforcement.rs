@@ -0,0 +1,39 @@
+use super::*;
+
+impl<DB> ServicesServer<DB>
+{
+    /// Move users off registered nicknames that they have held for longer than the
+    /// configured grace period without logging in to the owning account
+    pub(super) fn enforce_nick_registrations(&self)
+    {
+        let net = self.node.network();
+
+        // Only the active services node should enforce, to avoid duplicate events
+        if net.current_services().map(|s| s.server_id()) != Some(self.node.id())
+        {
+            return;
+        }
+
+        let now = sable_network::utils::now();
+
+        for registration in net.nick_registrations()
+        {
+            let Ok(binding) = net.nick_binding(&registration.nick()) else { continue };
+            let Ok(user) = binding.user() else { continue };
+            let Ok(owner) = registration.account() else { continue };
+
+            if user.account().ok().flatten().map(|a| a.id()) == Some(owner.id())
+            {
+                continue;
+            }
+
+            if now - binding.timestamp() < self.config.nick_enforcement_grace
+            {
+                continue;
+            }
+
+            tracing::debug!(nick=?binding.nick(), user=?user.id(), "enforcing nick registration");
+            self.node.submit_event(NicknameId::new(binding.nick()), ForceNickChange { user: user.id() });
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use std::str::FromStr;
+
+use super::*;
+use sable_network::prelude::*;
+
+pub struct SaslExternal;
+
+impl<DB: DatabaseConnection> SaslMechanism<DB> for SaslExternal
+{
+    fn step(&self, server: &ServicesServer<DB>, session: &mut SaslSession, data: Vec<u8>) -> SaslResult
+    {
+        let Some(fingerprint) = &session.fingerprint else {
+            tracing::debug!("sasl external without a client certificate");
+            return Ok(Fail);
+        };
+
+        let net = server.node.network();
+        let Some(account) = net.account_with_fingerprint(fingerprint) else {
+            tracing::debug!(?fingerprint, "no account for certificate fingerprint");
+            return Ok(Fail);
+        };
+
+        // The client may supply an authorisation identity; if it does, it must name
+        // the account that owns the certificate
+        if ! data.is_empty()
+        {
+            let authzid = Nickname::from_str(std::str::from_utf8(&data)?)?;
+            if authzid != account.name()
+            {
+                return Ok(Fail);
+            }
+        }
+
+        tracing::debug!(account_name=?account.name(), "sasl external login successful");
+        Ok(Success(account.id()))
+    }
+}
@@ -8,7 +8,7 @@ pub type SaslResult = Result<AuthenticateStatus, CommandError>;
 
 pub trait SaslMechanism<DB> : Send + Sync + 'static
 {
-    fn step(&self, server: &ServicesServer<DB>, session: &SaslSession, data: Vec<u8>) -> SaslResult;
+    fn step(&self, server: &ServicesServer<DB>, session: &mut SaslSession, data: Vec<u8>) -> SaslResult;
 }
 
 pub fn build_mechanisms<DB: DatabaseConnection>() -> HashMap<String, Box<dyn SaslMechanism<DB>>>
@@ -16,8 +16,14 @@ pub fn build_mechanisms<DB: DatabaseConnection>() -> HashMap<String, Box<dyn Sas
     let mut ret = HashMap::<String, Box<dyn SaslMechanism<DB>>>::new();
 
     ret.insert("PLAIN".to_owned(), Box::new(plain::SaslPlain));
+    ret.insert("EXTERNAL".to_owned(), Box::new(external::SaslExternal));
+    ret.insert("SCRAM-SHA-256".to_owned(), Box::new(scram::SaslScramSha256));
 
     ret
 }
 
-mod plain;
\ No newline at end of file
+mod plain;
+mod external;
+mod scram;
+
+pub use scram::scram_credentials;
\ No newline at end of file
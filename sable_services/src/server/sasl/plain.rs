@@ -7,7 +7,7 @@ pub struct SaslPlain;
 
 impl<DB: DatabaseConnection> SaslMechanism<DB> for SaslPlain
 {
-    fn step(&self, server: &ServicesServer<DB>, _session: &SaslSession, data: Vec<u8>) -> SaslResult
+    fn step(&self, server: &ServicesServer<DB>, _session: &mut SaslSession, data: Vec<u8>) -> SaslResult
     {
         let elements = data.split(|e| *e == 0).collect::<Vec<_>>();
 
@@ -34,6 +34,7 @@ impl<DB: DatabaseConnection> SaslMechanism<DB> for SaslPlain
         {
             Ok(true) => {
                 tracing::debug!(?account_name, "sasl login successful");
+                server.upgrade_auth(auth, password);
                 Ok(Success(account.id))
             }
             Ok(false) => {
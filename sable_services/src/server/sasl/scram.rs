@@ -0,0 +1,312 @@
+//! SCRAM-SHA-256, as defined in RFC 5802 and RFC 7677
+//!
+//! Channel binding is not supported. Passwords are used as given, without SASLprep
+//! normalisation, which is sufficient for ASCII passwords.
+
+use std::{str::FromStr, sync::OnceLock};
+
+use super::*;
+use sable_network::prelude::*;
+
+use base64::prelude::*;
+use hmac::{Hmac, Mac};
+use rand::{Rng, distributions::Alphanumeric};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+/// Iteration count used when generating new credentials
+const DEFAULT_ITERATIONS: u32 = 4096;
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8>
+{
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn salted_credentials(password: &[u8], salt: Vec<u8>, iterations: u32) -> ScramCredentials
+{
+    let mut salted_password = [0u8; 32];
+    pbkdf2::pbkdf2::<Hmac<Sha256>>(password, &salt, iterations, &mut salted_password);
+
+    let client_key = hmac(&salted_password, b"Client Key");
+
+    ScramCredentials {
+        salt,
+        iterations,
+        stored_key: Sha256::digest(client_key).to_vec(),
+        server_key: hmac(&salted_password, b"Server Key"),
+    }
+}
+
+/// Credentials for an account which doesn't exist, or has no SCRAM credentials. The salt is
+/// derived from the name, so that repeated attempts see the same one as they would for a
+/// real account; no proof will match the keys.
+fn dummy_credentials(username: &str) -> ScramCredentials
+{
+    static SALT_KEY: OnceLock<[u8; 32]> = OnceLock::new();
+    let salt_key = SALT_KEY.get_or_init(|| rand::thread_rng().gen());
+
+    ScramCredentials {
+        salt: hmac(salt_key, username.as_bytes())[..16].to_vec(),
+        iterations: DEFAULT_ITERATIONS,
+        stored_key: vec![0; 32],
+        server_key: vec![0; 32],
+    }
+}
+
+/// Generate SCRAM-SHA-256 credentials for the given password, with a random salt
+pub fn scram_credentials(password: &[u8]) -> ScramCredentials
+{
+    let salt: [u8; 16] = rand::thread_rng().gen();
+    salted_credentials(password, salt.to_vec(), DEFAULT_ITERATIONS)
+}
+
+/// Decode a `saslname` as defined in RFC 5802, which escapes ',' and '='
+fn decode_saslname(name: &str) -> Option<String>
+{
+    let mut ret = String::new();
+    let mut parts = name.split('=');
+
+    ret.push_str(parts.next()?);
+    for part in parts
+    {
+        let escaped = match part.get(..2)? {
+            "2C" => ',',
+            "3D" => '=',
+            _ => return None
+        };
+        ret.push(escaped);
+        ret.push_str(&part[2..]);
+    }
+    Some(ret)
+}
+
+/// Check the client-final message against the stored credentials. Returns the
+/// server-final message if the client's proof is valid.
+fn verify_client_final(credentials: &ScramCredentials, nonce: &str, gs2_header: &str,
+                       client_first_bare: &str, server_first: &str, client_final: &str) -> Option<String>
+{
+    let (without_proof, proof) = client_final.rsplit_once(",p=")?;
+
+    let mut attrs = without_proof.split(',');
+    let channel_binding = attrs.next()?.strip_prefix("c=")?;
+    let client_nonce = attrs.next()?.strip_prefix("r=")?;
+
+    if BASE64_STANDARD.decode(channel_binding).ok()? != gs2_header.as_bytes() || client_nonce != nonce
+    {
+        return None;
+    }
+
+    let auth_message = format!("{},{},{}", client_first_bare, server_first, without_proof);
+
+    let proof = BASE64_STANDARD.decode(proof).ok()?;
+    let client_signature = hmac(&credentials.stored_key, auth_message.as_bytes());
+    if proof.len() != client_signature.len()
+    {
+        return None;
+    }
+
+    let client_key: Vec<u8> = proof.iter().zip(client_signature).map(|(a, b)| a ^ b).collect();
+    if !bool::from(Sha256::digest(client_key).as_slice().ct_eq(&credentials.stored_key))
+    {
+        return None;
+    }
+
+    let server_signature = hmac(&credentials.server_key, auth_message.as_bytes());
+    Some(format!("v={}", BASE64_STANDARD.encode(server_signature)))
+}
+
+pub struct SaslScramSha256;
+
+impl SaslScramSha256
+{
+    fn client_first<DB: DatabaseConnection>(&self, server: &ServicesServer<DB>, session: &mut SaslSession, data: &str) -> SaslResult
+    {
+        // gs2-header is "n,," or "y,," optionally with an authzid; we don't support channel binding
+        let mut parts = data.splitn(3, ',');
+        let (Some(cbind_flag), Some(authzid), Some(client_first_bare)) = (parts.next(), parts.next(), parts.next()) else {
+            return Ok(Fail);
+        };
+
+        if cbind_flag != "n" && cbind_flag != "y"
+        {
+            return Ok(Fail);
+        }
+
+        let mut attrs = client_first_bare.split(',');
+        let (Some(username), Some(client_nonce)) = (
+            attrs.next().and_then(|a| a.strip_prefix("n=")).and_then(decode_saslname),
+            attrs.next().and_then(|a| a.strip_prefix("r="))
+        ) else {
+            return Ok(Fail);
+        };
+
+        // As with PLAIN, the authorisation identity, if given, must match the authentication identity
+        if let Some(authzid) = authzid.strip_prefix("a=")
+        {
+            if decode_saslname(authzid).as_ref() != Some(&username)
+            {
+                return Ok(Fail);
+            }
+        }
+
+        let account_name = Nickname::from_str(&username)?;
+        let (account, credentials) = match server.db.account_named(&account_name)
+        {
+            Ok(account) => match server.db.auth_for_account(account.id)?.scram_sha256
+            {
+                Some(credentials) => (Some(account.id), credentials),
+                None =>
+                {
+                    tracing::debug!(?account_name, "no scram credentials stored for account");
+                    (None, dummy_credentials(&username))
+                }
+            },
+            Err(_) =>
+            {
+                tracing::debug!(?account_name, "scram login for unknown account");
+                (None, dummy_credentials(&username))
+            }
+        };
+
+        let server_nonce: String = rand::thread_rng().sample_iter(&Alphanumeric).take(24).map(char::from).collect();
+        let nonce = format!("{}{}", client_nonce, server_nonce);
+
+        let server_first = format!("r={},s={},i={}", nonce, BASE64_STANDARD.encode(&credentials.salt), credentials.iterations);
+
+        session.scram = Some(ScramSessionState::AwaitingProof {
+            account,
+            nonce,
+            gs2_header: format!("{},{},", cbind_flag, authzid),
+            client_first_bare: client_first_bare.to_owned(),
+            server_first: server_first.clone(),
+        });
+
+        Ok(InProgress(server_first.into_bytes()))
+    }
+}
+
+impl<DB: DatabaseConnection> SaslMechanism<DB> for SaslScramSha256
+{
+    fn step(&self, server: &ServicesServer<DB>, session: &mut SaslSession, data: Vec<u8>) -> SaslResult
+    {
+        let data = std::str::from_utf8(&data)?;
+
+        match session.scram.take()
+        {
+            None => self.client_first(server, session, data),
+            Some(ScramSessionState::AwaitingProof { account: None, .. }) =>
+            {
+                tracing::debug!("scram proof for unknown account or missing credentials");
+                Ok(Fail)
+            }
+            Some(ScramSessionState::AwaitingProof { account: Some(account), nonce, gs2_header, client_first_bare, server_first }) =>
+            {
+                let Some(credentials) = server.db.auth_for_account(account)?.scram_sha256 else {
+                    return Ok(Fail);
+                };
+
+                match verify_client_final(&credentials, &nonce, &gs2_header, &client_first_bare, &server_first, data)
+                {
+                    Some(server_final) =>
+                    {
+                        session.scram = Some(ScramSessionState::AwaitingAck { account });
+                        Ok(InProgress(server_final.into_bytes()))
+                    }
+                    None =>
+                    {
+                        tracing::debug!(?account, "scram proof verification failed");
+                        Ok(Fail)
+                    }
+                }
+            }
+            Some(ScramSessionState::AwaitingAck { account }) =>
+            {
+                tracing::debug!(?account, "sasl login successful");
+                Ok(Success(account))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::database::sqlite::SqliteDatabase;
+    use crate::server::test_utils::*;
+
+    // Example exchange from RFC 7677, section 3
+    #[test]
+    fn rfc7677_exchange()
+    {
+        let salt = BASE64_STANDARD.decode("W22ZaJ0SNY7soEsUEjb6gQ==").unwrap();
+        let credentials = salted_credentials(b"pencil", salt, 4096);
+
+        let nonce = "rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0";
+        let client_first_bare = "n=user,r=rOprNGfwEbeRWgbNEkqO";
+        let server_first = format!("r={},s=W22ZaJ0SNY7soEsUEjb6gQ==,i=4096", nonce);
+        let client_final = format!("c=biws,r={},p=dHzbZapWIk4jUhN+Ute9ytag9zjfMHgsqmmiz7AndVQ=", nonce);
+
+        let server_final = verify_client_final(&credentials, nonce, "n,,", client_first_bare, &server_first, &client_final);
+        assert_eq!(server_final.as_deref(), Some("v=6rriTRBi23WpRR/wtup+mMhUZUn/dB5nLTJRsjl95G4="));
+
+        let wrong = salted_credentials(b"pencils", BASE64_STANDARD.decode("W22ZaJ0SNY7soEsUEjb6gQ==").unwrap(), 4096);
+        assert!(verify_client_final(&wrong, nonce, "n,,", client_first_bare, &server_first, &client_final).is_none());
+    }
+
+    /// Send a client-first message for the given name, returning the server-first response
+    fn start_exchange(server: &ServicesServer<SqliteDatabase>, session: &mut SaslSession, name: &str) -> String
+    {
+        let response = SaslScramSha256.step(server, session, format!("n,,n={},r=clientnonce", name).into_bytes()).unwrap();
+        let InProgress(server_first) = response else { panic!("Unexpected response {:?}", response) };
+        String::from_utf8(server_first).unwrap()
+    }
+
+    fn new_session() -> SaslSession
+    {
+        let id = ObjectIdGenerator::new(ServerId::new(1), EpochId::new(1)).next_sasl_session();
+        SaslSession { id, mechanism: "SCRAM-SHA-256".to_owned(), fingerprint: None, scram: None }
+    }
+
+    #[test]
+    fn unknown_account_is_indistinguishable()
+    {
+        let server = test_server(&spool_directory("scram-unknown-account"));
+        let account = state::Account { id: server.node.ids().next_account(), name: Nickname::from_str("alice").unwrap(), authorised_fingerprints: Vec::new() };
+        let auth = AccountAuth {
+            account: account.id,
+            password_hash: String::new(),
+            scram_sha256: Some(scram_credentials(b"pencil")),
+            recovery_token: None,
+            email: None,
+        };
+        server.db.new_account(account, auth).unwrap();
+
+        let salt_and_iterations = |server_first: &str| server_first.split_once(",s=").unwrap().1.to_owned();
+
+        let known = start_exchange(&server, &mut new_session(), "alice");
+        let mut session = new_session();
+        let unknown = start_exchange(&server, &mut session, "nobody");
+
+        assert!(known.ends_with(",i=4096"));
+        assert!(unknown.ends_with(",i=4096"));
+        assert_ne!(salt_and_iterations(&known), salt_and_iterations(&unknown));
+
+        // The same name gets the same salt each time, as a real account would
+        assert_eq!(salt_and_iterations(&start_exchange(&server, &mut new_session(), "nobody")), salt_and_iterations(&unknown));
+
+        // The exchange only fails once the proof is sent
+        let nonce = unknown.strip_prefix("r=").unwrap().split(',').next().unwrap();
+        let client_final = format!("c=biws,r={},p={}", nonce, BASE64_STANDARD.encode([0u8; 32]));
+        assert!(matches!(SaslScramSha256.step(&server, &mut session, client_final.into_bytes()).unwrap(), Fail));
+    }
+
+    #[test]
+    fn saslname_escapes()
+    {
+        assert_eq!(decode_saslname("a=2Cb=3Dc").as_deref(), Some("a,b=c"));
+        assert_eq!(decode_saslname("bad=name"), None);
+    }
+}
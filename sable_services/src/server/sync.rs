@@ -145,6 +145,8 @@ impl<DB> ServicesServer<DB>
         }
 
         // Finally, set ourselves as the active services node
-        self.node.submit_event(self.node.id(), IntroduceServices { sasl_mechanisms: vec!["PLAIN".to_owned()] });
+        let mut sasl_mechanisms: Vec<_> = self.sasl_mechanisms.keys().cloned().collect();
+        sasl_mechanisms.sort();
+        self.node.submit_event(self.node.id(), IntroduceServices { sasl_mechanisms });
     }
 }
\ No newline at end of file
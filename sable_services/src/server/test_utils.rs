@@ -0,0 +1,72 @@
+//! Helpers for tests which need a running services instance
+
+use super::*;
+use crate::database::sqlite::SqliteDatabase;
+use sable_network::{
+    network::Network,
+    validated::ServerName,
+    sync::ReplicatedEventLog,
+};
+use std::{path::{Path, PathBuf}, str::FromStr, time::Duration};
+use tokio::sync::mpsc::unbounded_channel;
+
+/// Build a services instance backed by an in-memory database, which writes its mail to
+/// the given spool directory. Paths are relative to the crate, where tests are run.
+pub fn test_server(spool: &Path) -> ServicesServer<SqliteDatabase>
+{
+    let sync_config = serde_json::from_value(serde_json::json!({
+        "fanout": 1,
+        "ca_file": "../configs/ca_cert.pem",
+        "peers": [
+            { "name": "services.test", "address": "127.0.1.4:6668", "fingerprint": "" }
+        ]
+    })).unwrap();
+    let node_config = serde_json::from_value(serde_json::json!({
+        "listen_addr": "127.0.1.4:6668",
+        "cert_file": "../configs/services.pem",
+        "key_file": "../configs/services.key"
+    })).unwrap();
+    let net_config = sable_server::config::load_network_config("../configs/network_config.json").unwrap();
+
+    let server_id = ServerId::new(99);
+    let epoch = EpochId::new(1);
+    let (server_send, server_recv) = unbounded_channel();
+    let (history_send, history_recv) = unbounded_channel();
+
+    let log = Arc::new(ReplicatedEventLog::new(server_id, epoch, server_send, sync_config, node_config));
+    let node = Arc::new(NetworkNode::new(server_id, epoch, ServerName::from_str("services.test").unwrap(),
+                                         Network::new(net_config), log, server_recv, history_send, None,
+                                         sable_network::policy::StandardPolicyService::new()));
+
+    let config = ServicesConfig {
+        database: ":memory:".to_owned(),
+        default_roles: [ChannelRoleName::BuiltinFounder, ChannelRoleName::BuiltinOp, ChannelRoleName::BuiltinVoice]
+                            .into_iter().map(|role| (role, Vec::new())).collect(),
+        nick_enforcement_grace: 60,
+        mailer: Some(mailer::MailerConfig::Spool { from: "services@example.com".to_owned(), directory: spool.to_owned() }),
+    };
+
+    let tls_data = sable_network::config::TlsData { key: Vec::new(), cert_chain: Vec::new() };
+    ServicesServer::new(config, &tls_data, node, history_recv)
+}
+
+pub fn spool_directory(name: &str) -> PathBuf
+{
+    std::env::temp_dir().join(format!("sable-{}-{}", name, std::process::id()))
+}
+
+/// Wait for the mailer thread to deliver a message, and extract the verification code from it
+pub fn read_verification_code(spool: &Path) -> String
+{
+    for _ in 0..100
+    {
+        if let Some(Ok(entry)) = std::fs::read_dir(spool).into_iter().flatten().next()
+        {
+            let contents = std::fs::read_to_string(entry.path()).unwrap();
+            let line = contents.lines().find(|l| l.starts_with("To complete registration")).unwrap();
+            return line.rsplit(' ').next().unwrap().to_owned();
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    panic!("No verification email was sent");
+}